@@ -0,0 +1,183 @@
+//! The allocator interface used by `HeaderVec`.
+//!
+//! With the `allocator-api2` feature enabled, this is the `Allocator` trait from that crate and
+//! any allocator implementing it can be used. Otherwise, only `Global` is available.
+
+use crate::error::{handle_reserve, TryReserveError};
+use alloc::alloc::Layout;
+use core::ptr::{self, NonNull};
+
+#[cfg(feature = "allocator-api2")]
+pub use allocator_api2::alloc::{Allocator, Global};
+
+#[cfg(not(feature = "allocator-api2"))]
+pub use self::fallback::{Allocator, Global};
+
+#[cfg(not(feature = "allocator-api2"))]
+mod fallback {
+    use alloc::alloc::{alloc, dealloc, realloc, Layout};
+    use core::ptr::NonNull;
+
+    /// The error returned when an allocator fails.
+    #[derive(Copy, Clone, PartialEq, Eq, Debug)]
+    pub struct AllocError;
+
+    /// A minimal stand-in for the unstable `Allocator` trait, implemented only by `Global`.
+    ///
+    /// # Safety
+    /// Implementors must return blocks of memory that fit the requested layouts.
+    pub unsafe trait Allocator {
+        fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError>;
+
+        /// # Safety
+        /// `ptr` must have been allocated by this allocator with `layout`.
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout);
+
+        /// # Safety
+        /// `ptr` must have been allocated by this allocator with `old_layout`, and
+        /// `new_layout.size()` must not be less than `old_layout.size()`.
+        unsafe fn grow(
+            &self,
+            ptr: NonNull<u8>,
+            old_layout: Layout,
+            new_layout: Layout,
+        ) -> Result<NonNull<[u8]>, AllocError>;
+
+        /// # Safety
+        /// `ptr` must have been allocated by this allocator with `old_layout`, and
+        /// `new_layout.size()` must not be greater than `old_layout.size()`.
+        unsafe fn shrink(
+            &self,
+            ptr: NonNull<u8>,
+            old_layout: Layout,
+            new_layout: Layout,
+        ) -> Result<NonNull<[u8]>, AllocError>;
+    }
+
+    /// The global memory allocator.
+    #[derive(Copy, Clone, Default, Debug)]
+    pub struct Global;
+
+    fn dangling(layout: Layout) -> NonNull<[u8]> {
+        NonNull::slice_from_raw_parts(super::dangling(layout), 0)
+    }
+
+    fn non_null(ptr: *mut u8, size: usize) -> Result<NonNull<[u8]>, AllocError> {
+        let ptr = NonNull::new(ptr).ok_or(AllocError)?;
+        Ok(NonNull::slice_from_raw_parts(ptr, size))
+    }
+
+    unsafe impl Allocator for Global {
+        fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+            if layout.size() == 0 {
+                return Ok(dangling(layout));
+            }
+            non_null(unsafe { alloc(layout) }, layout.size())
+        }
+
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+            if layout.size() != 0 {
+                dealloc(ptr.as_ptr(), layout);
+            }
+        }
+
+        unsafe fn grow(
+            &self,
+            ptr: NonNull<u8>,
+            old_layout: Layout,
+            new_layout: Layout,
+        ) -> Result<NonNull<[u8]>, AllocError> {
+            if old_layout.size() == 0 {
+                return self.allocate(new_layout);
+            }
+            let new_ptr = realloc(ptr.as_ptr(), old_layout, new_layout.size());
+            non_null(new_ptr, new_layout.size())
+        }
+
+        unsafe fn shrink(
+            &self,
+            ptr: NonNull<u8>,
+            old_layout: Layout,
+            new_layout: Layout,
+        ) -> Result<NonNull<[u8]>, AllocError> {
+            if new_layout.size() == 0 {
+                self.deallocate(ptr, old_layout);
+                return Ok(dangling(new_layout));
+            }
+            let new_ptr = realloc(ptr.as_ptr(), old_layout, new_layout.size());
+            non_null(new_ptr, new_layout.size())
+        }
+    }
+}
+
+/// Returns a dangling pointer aligned for `layout`, used in place of zero-size allocations.
+pub fn dangling(layout: Layout) -> NonNull<u8> {
+    // the address is the alignment, with no provenance since it's never dereferenced
+    let ptr = ptr::null_mut::<u8>().wrapping_add(layout.align());
+    unsafe { NonNull::new_unchecked(ptr) }
+}
+
+/// Allocates a block of memory for `layout`.
+/// Zero-size layouts get a dangling pointer without calling the allocator.
+pub fn try_allocate<A: Allocator>(
+    alloc: &A,
+    layout: Layout,
+) -> Result<NonNull<u8>, TryReserveError> {
+    if layout.size() == 0 {
+        return Ok(dangling(layout));
+    }
+    match alloc.allocate(layout) {
+        Ok(ptr) => Ok(ptr.cast()),
+        Err(_) => Err(TryReserveError::AllocError { layout }),
+    }
+}
+
+/// Allocates a block of memory for `layout`, calling `handle_alloc_error` if the allocator fails.
+pub fn allocate<A: Allocator>(alloc: &A, layout: Layout) -> NonNull<u8> {
+    handle_reserve(try_allocate(alloc, layout))
+}
+
+/// Frees a block of memory allocated with `try_allocate` or `try_reallocate`.
+/// Does nothing for zero-size layouts.
+pub unsafe fn deallocate<A: Allocator>(alloc: &A, ptr: NonNull<u8>, layout: Layout) {
+    if layout.size() != 0 {
+        alloc.deallocate(ptr, layout);
+    }
+}
+
+/// Resizes a block of memory from `old_layout` to `new_layout`.
+/// The allocator is only called when both layouts have a non-zero size.
+pub unsafe fn try_reallocate<A: Allocator>(
+    alloc: &A,
+    ptr: NonNull<u8>,
+    old_layout: Layout,
+    new_layout: Layout,
+) -> Result<NonNull<u8>, TryReserveError> {
+    if old_layout.size() == 0 {
+        return try_allocate(alloc, new_layout);
+    }
+    if new_layout.size() == 0 {
+        deallocate(alloc, ptr, old_layout);
+        return Ok(dangling(new_layout));
+    }
+    let result = if new_layout.size() >= old_layout.size() {
+        alloc.grow(ptr, old_layout, new_layout)
+    } else {
+        alloc.shrink(ptr, old_layout, new_layout)
+    };
+    match result {
+        Ok(ptr) => Ok(ptr.cast()),
+        Err(_) => Err(TryReserveError::AllocError { layout: new_layout }),
+    }
+}
+
+/// Resizes a block of memory from `old_layout` to `new_layout`, calling `handle_alloc_error` if the
+/// allocator fails.
+pub unsafe fn reallocate<A: Allocator>(
+    alloc: &A,
+    ptr: NonNull<u8>,
+    old_layout: Layout,
+    new_layout: Layout,
+) -> NonNull<u8> {
+    handle_reserve(try_reallocate(alloc, ptr, old_layout, new_layout))
+}
@@ -0,0 +1,15 @@
+use core::sync::atomic::AtomicUsize;
+
+header_rc! {
+    /// A thread-safe reference-counted `HeaderSlice`, storing its reference counts in the same
+    /// allocation as its header and body.
+    pub struct HeaderArc;
+    /// A weak reference to the contents of a `HeaderArc`.
+    pub struct Weak;
+    count: AtomicUsize;
+}
+
+unsafe impl<H: Send + Sync, T: Send + Sync> Send for HeaderArc<H, T> {}
+unsafe impl<H: Send + Sync, T: Send + Sync> Sync for HeaderArc<H, T> {}
+unsafe impl<H: Send + Sync, T: Send + Sync> Send for Weak<H, T> {}
+unsafe impl<H: Send + Sync, T: Send + Sync> Sync for Weak<H, T> {}
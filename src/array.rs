@@ -0,0 +1,278 @@
+use crate::error::CapacityError;
+use crate::inline::Inline;
+use crate::slice::HeaderSlice;
+use core::borrow::{Borrow, BorrowMut};
+use core::cmp::Ordering;
+use core::fmt::{self, Debug};
+use core::hash::{self, Hash};
+use core::mem;
+use core::ops::{Deref, DerefMut};
+use core::ptr;
+
+/// A vector with a header, like `HeaderVec`, that stores its header and up to `CAP` elements
+/// inline and never allocates. Operations that would exceed the capacity return a
+/// `CapacityError` instead of reallocating.
+///
+/// `new` is a `const fn`, so an `ArrayHeaderVec` can be placed in a `static` or `const`.
+pub struct ArrayHeaderVec<H, T, const CAP: usize> {
+    data: Inline<H, T, CAP>,
+    len: usize,
+}
+
+impl<H, T, const CAP: usize> ArrayHeaderVec<H, T, CAP> {
+    /// Creates an empty vector with the given header.
+    pub const fn new(head: H) -> Self {
+        Self {
+            data: Inline::new(head),
+            len: 0,
+        }
+    }
+
+    /// Creates a vector from the given header and iterator, or returns an error with the first
+    /// value that doesn't fit.
+    pub fn try_from_iter<I: IntoIterator<Item = T>>(
+        head: H,
+        iter: I,
+    ) -> Result<Self, CapacityError<T>> {
+        let mut this = Self::new(head);
+        this.try_extend(iter)?;
+        Ok(this)
+    }
+
+    /// The number of elements the vector can hold.
+    pub const fn capacity(&self) -> usize {
+        CAP
+    }
+
+    /// The number of elements that can be added before the vector is full.
+    pub const fn remaining_capacity(&self) -> usize {
+        CAP - self.len
+    }
+
+    /// Returns `true` if the vector holds `CAP` elements.
+    pub const fn is_full(&self) -> bool {
+        self.len == CAP
+    }
+
+    /// Push a value to the end of the vector, or return it in an error if the vector is full.
+    pub fn push(&mut self, val: T) -> Result<(), CapacityError<T>> {
+        if self.is_full() {
+            return Err(CapacityError::new(val));
+        }
+        unsafe { self.push_unchecked(val) };
+        Ok(())
+    }
+
+    /// unsafe because the vector must not be full.
+    unsafe fn push_unchecked(&mut self, val: T) {
+        self.data.body.get_unchecked_mut(self.len).write(val);
+        self.len += 1;
+    }
+
+    /// Removes the last value from the vector and returns it, or `None` if it is empty.
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        Some(unsafe { ptr::read(self.data.body[self.len].as_ptr()) })
+    }
+
+    /// Inserts a value at `index`, shifting all elements after it to the right, or returns it in
+    /// an error if the vector is full.
+    /// Panics if `index > len`.
+    pub fn insert(&mut self, index: usize, val: T) -> Result<(), CapacityError<T>> {
+        let len = self.len;
+        assert!(
+            index <= len,
+            "insertion index {} is out of bounds for length {}",
+            index,
+            len
+        );
+        if self.is_full() {
+            return Err(CapacityError::new(val));
+        }
+        unsafe {
+            let p = self.data.body.as_mut_ptr().add(index);
+            ptr::copy(p, p.add(1), len - index);
+            p.write(mem::MaybeUninit::new(val));
+        }
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Removes a value at the given index, if it exists.
+    /// All entries after `index` will be shifted to the left.
+    pub fn remove(&mut self, index: usize) -> Option<T> {
+        let len = self.len;
+        if index >= len {
+            return None;
+        }
+        unsafe {
+            let p = self.data.body.as_mut_ptr().add(index);
+            let val = ptr::read(p).assume_init();
+            ptr::copy(p.add(1), p, len - index - 1);
+            self.len -= 1;
+            Some(val)
+        }
+    }
+
+    /// Removes a value at the given index, if it exists, and replaces it with the last element.
+    pub fn swap_remove(&mut self, index: usize) -> Option<T> {
+        let len = self.len;
+        if index >= len {
+            return None;
+        }
+        self.body.swap(index, len - 1);
+        self.pop()
+    }
+
+    /// Drops all elements after `new_len`.
+    /// Panics if `new_len > len`.
+    pub fn truncate(&mut self, new_len: usize) {
+        assert!(new_len <= self.len);
+        let old_len = mem::replace(&mut self.len, new_len);
+        unsafe { self.data.drop_range(new_len, old_len) };
+    }
+
+    /// Drops all elements in the vector.
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    /// Pushes each value from the iterator, stopping with an error holding the first value that
+    /// doesn't fit.
+    pub fn try_extend<I: IntoIterator<Item = T>>(
+        &mut self,
+        iter: I,
+    ) -> Result<(), CapacityError<T>> {
+        iter.into_iter().try_for_each(|x| self.push(x))
+    }
+
+    /// Clones and appends every element of `src`, or returns an error without changing the
+    /// vector if they don't all fit.
+    pub fn try_extend_from_slice(&mut self, src: &[T]) -> Result<(), CapacityError>
+    where
+        T: Clone,
+    {
+        if src.len() > self.remaining_capacity() {
+            return Err(CapacityError::new(()));
+        }
+        for x in src {
+            unsafe { self.push_unchecked(x.clone()) };
+        }
+        Ok(())
+    }
+}
+
+impl<H, T, const CAP: usize> Deref for ArrayHeaderVec<H, T, CAP> {
+    type Target = HeaderSlice<H, T>;
+    fn deref(&self) -> &Self::Target {
+        unsafe { self.data.as_header_slice(self.len) }
+    }
+}
+
+impl<H, T, const CAP: usize> DerefMut for ArrayHeaderVec<H, T, CAP> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { self.data.as_header_slice_mut(self.len) }
+    }
+}
+
+impl<H, T, const CAP: usize> AsRef<HeaderSlice<H, T>> for ArrayHeaderVec<H, T, CAP> {
+    fn as_ref(&self) -> &HeaderSlice<H, T> {
+        self.deref()
+    }
+}
+
+impl<H, T, const CAP: usize> AsMut<HeaderSlice<H, T>> for ArrayHeaderVec<H, T, CAP> {
+    fn as_mut(&mut self) -> &mut HeaderSlice<H, T> {
+        self.deref_mut()
+    }
+}
+
+impl<H, T, const CAP: usize> Borrow<HeaderSlice<H, T>> for ArrayHeaderVec<H, T, CAP> {
+    fn borrow(&self) -> &HeaderSlice<H, T> {
+        self.deref()
+    }
+}
+
+impl<H, T, const CAP: usize> BorrowMut<HeaderSlice<H, T>> for ArrayHeaderVec<H, T, CAP> {
+    fn borrow_mut(&mut self) -> &mut HeaderSlice<H, T> {
+        self.deref_mut()
+    }
+}
+
+impl<H, T, const CAP: usize> Drop for ArrayHeaderVec<H, T, CAP> {
+    fn drop(&mut self) {
+        // the header is dropped with the fields
+        unsafe { self.data.drop_range(0, self.len) };
+    }
+}
+
+impl<H: Clone, T: Clone, const CAP: usize> Clone for ArrayHeaderVec<H, T, CAP> {
+    fn clone(&self) -> Self {
+        let mut out = Self::new(self.head.clone());
+        for x in &self.body {
+            // the clone has the same capacity, so it can't be full
+            unsafe { out.push_unchecked(x.clone()) };
+        }
+        out
+    }
+}
+
+/// Panics if the vector becomes full. Use `try_extend` to handle that case.
+impl<H, T, const CAP: usize> Extend<T> for ArrayHeaderVec<H, T, CAP> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        if self.try_extend(iter).is_err() {
+            panic!("ArrayHeaderVec capacity {} exceeded", CAP);
+        }
+    }
+}
+
+impl<H, T, Rhs: ?Sized, const CAP: usize> PartialEq<Rhs> for ArrayHeaderVec<H, T, CAP>
+where
+    H: PartialEq,
+    T: PartialEq,
+    Rhs: Borrow<HeaderSlice<H, T>>,
+{
+    fn eq(&self, rhs: &Rhs) -> bool {
+        self.deref() == rhs.borrow()
+    }
+}
+
+impl<H: Eq, T: Eq, const CAP: usize> Eq for ArrayHeaderVec<H, T, CAP> {}
+
+impl<H, T, Rhs: ?Sized, const CAP: usize> PartialOrd<Rhs> for ArrayHeaderVec<H, T, CAP>
+where
+    H: PartialOrd,
+    T: PartialOrd,
+    Rhs: Borrow<HeaderSlice<H, T>>,
+{
+    fn partial_cmp(&self, rhs: &Rhs) -> Option<Ordering> {
+        self.deref().partial_cmp(rhs.borrow())
+    }
+}
+
+impl<H: Ord, T: Ord, const CAP: usize> Ord for ArrayHeaderVec<H, T, CAP> {
+    fn cmp(&self, rhs: &Self) -> Ordering {
+        self.deref().cmp(rhs.deref())
+    }
+}
+
+impl<H: Hash, T: Hash, const CAP: usize> Hash for ArrayHeaderVec<H, T, CAP> {
+    fn hash<S: hash::Hasher>(&self, state: &mut S) {
+        self.deref().hash(state)
+    }
+}
+
+impl<H: Debug, T: Debug, const CAP: usize> Debug for ArrayHeaderVec<H, T, CAP> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.deref().fmt(f)
+    }
+}
+
+impl<H: Default, T, const CAP: usize> Default for ArrayHeaderVec<H, T, CAP> {
+    fn default() -> Self {
+        Self::new(H::default())
+    }
+}
@@ -0,0 +1,127 @@
+//! Casting between byte slices and `HeaderSlice`s of plain-old-data types, enabled with the
+//! `bytemuck` feature.
+
+use crate::error::CastError;
+use crate::view::{HeaderSliceMut, HeaderSliceRef};
+use crate::HeaderSlice;
+use bytemuck::{NoUninit, Pod};
+use core::alloc::Layout;
+use core::mem;
+use core::ptr;
+use core::slice;
+
+impl<H, T> HeaderSlice<H, T> {
+    /// Returns the offset of the body from the start of the header.
+    fn body_offset() -> usize {
+        Layout::new::<H>()
+            .extend(Layout::new::<[T; 0]>())
+            .unwrap()
+            .1
+    }
+
+    /// Checks that `bytes` can be cast to a header and body and returns the length of the body.
+    /// If `padded` is set, the bytes must also include the trailing padding of a `HeaderSlice`.
+    fn body_len_for_bytes(bytes: &[u8], padded: bool) -> Result<usize, CastError> {
+        let align = mem::align_of::<H>().max(mem::align_of::<T>());
+        if bytes.as_ptr() as usize % align != 0 {
+            return Err(CastError::Misaligned);
+        }
+        let offset = Self::body_offset();
+        if bytes.len() < offset {
+            return Err(CastError::TooShort);
+        }
+        // with trailing padding, padding that could hold whole elements is read as part of the
+        // body
+        let len = match mem::size_of::<T>() {
+            0 => 0,
+            size => (bytes.len() - offset) / size,
+        };
+        let size = if padded {
+            Self::layout_for_len(len).size()
+        } else {
+            offset + len * mem::size_of::<T>()
+        };
+        if size != bytes.len() {
+            return Err(CastError::InvalidLength);
+        }
+        Ok(len)
+    }
+
+    /// Reinterprets a byte slice as a `HeaderSlice`, with the body made up of the bytes after the
+    /// header.
+    /// The slice must be aligned for `H` and `T`, and its length must be exactly the size of a
+    /// `HeaderSlice<H, T>`, including any trailing padding. Use `HeaderSliceRef::try_from_bytes`
+    /// for bytes without the trailing padding, such as those returned by `as_bytes`.
+    pub fn try_from_bytes(bytes: &[u8]) -> Result<&Self, CastError>
+    where
+        H: Pod,
+        T: Pod,
+    {
+        let len = Self::body_len_for_bytes(bytes, true)?;
+        let ptr = ptr::slice_from_raw_parts(bytes.as_ptr() as *const T, len) as *const Self;
+        Ok(unsafe { &*ptr })
+    }
+
+    /// Reinterprets a mutable byte slice as a `HeaderSlice`, with the body made up of the bytes
+    /// after the header.
+    /// The slice must be aligned for `H` and `T`, and its length must be exactly the size of a
+    /// `HeaderSlice<H, T>`, including any trailing padding. Use
+    /// `HeaderSliceMut::try_from_bytes_mut` for bytes without the trailing padding.
+    pub fn try_from_bytes_mut(bytes: &mut [u8]) -> Result<&mut Self, CastError>
+    where
+        H: Pod,
+        T: Pod,
+    {
+        let len = Self::body_len_for_bytes(bytes, true)?;
+        let ptr = ptr::slice_from_raw_parts_mut(bytes.as_mut_ptr() as *mut T, len) as *mut Self;
+        Ok(unsafe { &mut *ptr })
+    }
+
+    /// Returns the bytes of the header and body, without any trailing padding after the body.
+    /// Panics if there is padding between the header and the body.
+    pub fn as_bytes(&self) -> &[u8]
+    where
+        H: NoUninit,
+        T: NoUninit,
+    {
+        let offset = Self::body_offset();
+        assert_eq!(
+            offset,
+            mem::size_of::<H>(),
+            "there is padding between the header and the body"
+        );
+        let len = offset + mem::size_of_val(&self.body);
+        unsafe { slice::from_raw_parts(self as *const Self as *const u8, len) }
+    }
+}
+
+impl<'a, H: Pod, T: Pod> HeaderSliceRef<'a, H, T> {
+    /// Reinterprets a byte slice as a header followed by a body, such as the bytes returned by
+    /// `HeaderSlice::as_bytes`.
+    /// The slice must be aligned for `H` and `T`, and its length must be exactly the size of the
+    /// header and a whole number of elements, without trailing padding.
+    pub fn try_from_bytes(bytes: &'a [u8]) -> Result<Self, CastError> {
+        let len = HeaderSlice::<H, T>::body_len_for_bytes(bytes, false)?;
+        let offset = HeaderSlice::<H, T>::body_offset();
+        let ptr = bytes.as_ptr();
+        unsafe {
+            let body = slice::from_raw_parts(ptr.add(offset) as *const T, len);
+            Ok(Self::new(&*(ptr as *const H), body))
+        }
+    }
+}
+
+impl<'a, H: Pod, T: Pod> HeaderSliceMut<'a, H, T> {
+    /// Reinterprets a mutable byte slice as a header followed by a body.
+    /// The slice must be aligned for `H` and `T`, and its length must be exactly the size of the
+    /// header and a whole number of elements, without trailing padding.
+    pub fn try_from_bytes_mut(bytes: &'a mut [u8]) -> Result<Self, CastError> {
+        let len = HeaderSlice::<H, T>::body_len_for_bytes(bytes, false)?;
+        let offset = HeaderSlice::<H, T>::body_offset();
+        let ptr = bytes.as_mut_ptr();
+        unsafe {
+            let body = slice::from_raw_parts_mut(ptr.add(offset) as *mut T, len);
+            Ok(Self::new(&mut *(ptr as *mut H), body))
+        }
+    }
+}
@@ -0,0 +1,494 @@
+use core::cell::Cell;
+use core::sync::atomic::{self, AtomicUsize};
+
+/// A reference count, either shared within a thread or between threads.
+pub trait RefCount {
+    fn new(count: usize) -> Self;
+
+    fn get(&self) -> usize;
+
+    /// Increments the count, returning its previous value.
+    fn increment(&self) -> usize;
+
+    /// Decrements the count, returning its previous value.
+    fn decrement(&self) -> usize;
+
+    /// Increments the count unless it is zero. Returns whether it was incremented.
+    fn increment_if_nonzero(&self) -> bool;
+
+    /// Sets the count to `new` if it is equal to `current`. Returns whether it was set.
+    fn replace_if(&self, current: usize, new: usize) -> bool;
+}
+
+impl RefCount for Cell<usize> {
+    fn new(count: usize) -> Self {
+        Cell::new(count)
+    }
+
+    fn get(&self) -> usize {
+        Cell::get(self)
+    }
+
+    fn increment(&self) -> usize {
+        let old = Cell::get(self);
+        self.set(old.checked_add(1).expect("reference count overflow"));
+        old
+    }
+
+    fn decrement(&self) -> usize {
+        let old = Cell::get(self);
+        self.set(old - 1);
+        old
+    }
+
+    fn increment_if_nonzero(&self) -> bool {
+        if Cell::get(self) == 0 {
+            return false;
+        }
+        self.increment();
+        true
+    }
+
+    fn replace_if(&self, current: usize, new: usize) -> bool {
+        if Cell::get(self) != current {
+            return false;
+        }
+        self.set(new);
+        true
+    }
+}
+
+impl RefCount for AtomicUsize {
+    fn new(count: usize) -> Self {
+        AtomicUsize::new(count)
+    }
+
+    fn get(&self) -> usize {
+        self.load(atomic::Ordering::Acquire)
+    }
+
+    fn increment(&self) -> usize {
+        let old = self.fetch_add(1, atomic::Ordering::Relaxed);
+        assert!(old <= isize::MAX as usize, "reference count overflow");
+        old
+    }
+
+    fn decrement(&self) -> usize {
+        let old = self.fetch_sub(1, atomic::Ordering::Release);
+        if old == 1 {
+            // make sure every other owner is done with the value before it's dropped
+            atomic::fence(atomic::Ordering::Acquire);
+        }
+        old
+    }
+
+    fn increment_if_nonzero(&self) -> bool {
+        self.fetch_update(atomic::Ordering::Acquire, atomic::Ordering::Relaxed, |n| {
+            if n == 0 {
+                None
+            } else {
+                Some(n + 1)
+            }
+        })
+        .is_ok()
+    }
+
+    fn replace_if(&self, current: usize, new: usize) -> bool {
+        self.compare_exchange(
+            current,
+            new,
+            atomic::Ordering::Acquire,
+            atomic::Ordering::Relaxed,
+        )
+        .is_ok()
+    }
+}
+
+/// The reference counts and length of a reference-counted header slice, stored in front of the
+/// header.
+#[repr(C)]
+pub struct Counts<C> {
+    pub strong: C,
+    /// Weak references, plus one shared by all strong references.
+    pub weak: C,
+    pub len: usize,
+}
+
+/// Defines a reference-counted header slice type and its weak counterpart, storing the counts in
+/// the same allocation as the header and body.
+macro_rules! header_rc {
+    (
+        $(#[$rc_attr:meta])*
+        pub struct $Rc:ident;
+        $(#[$weak_attr:meta])*
+        pub struct $Weak:ident;
+        count: $Count:ty;
+    ) => {
+        use $crate::counted::RefCount as _;
+
+        type CountedPair<H, T> = $crate::pair::Pair<
+            $crate::counted::Counts<$Count>,
+            $crate::pair::Pair<H, core::mem::MaybeUninit<T>>,
+        >;
+
+        $(#[$rc_attr])*
+        pub struct $Rc<H, T> {
+            ptr: core::ptr::NonNull<CountedPair<H, T>>,
+            _marker: core::marker::PhantomData<$crate::pair::Pair<H, T>>,
+        }
+
+        $(#[$weak_attr])*
+        pub struct $Weak<H, T> {
+            ptr: core::ptr::NonNull<CountedPair<H, T>>,
+            _marker: core::marker::PhantomData<$crate::pair::Pair<H, T>>,
+        }
+
+        /// Returns the `Layout` of an allocation holding the given number of elements, and the
+        /// offset of the header within it.
+        fn layout_and_offset<H, T>(len: usize) -> (alloc::alloc::Layout, usize) {
+            $crate::slice::HeaderSlice::<H, T>::prefixed_layout_for_len::<
+                $crate::counted::Counts<$Count>,
+            >(len)
+        }
+
+        fn counts<'a, H, T>(
+            ptr: core::ptr::NonNull<CountedPair<H, T>>,
+        ) -> &'a $crate::counted::Counts<$Count> {
+            unsafe { &(*ptr.as_ptr()).0 }
+        }
+
+        /// Frees the allocation behind `ptr` without dropping its contents.
+        unsafe fn dealloc<H, T>(ptr: core::ptr::NonNull<CountedPair<H, T>>) {
+            let (layout, _) = layout_and_offset::<H, T>(counts(ptr).len);
+            $crate::allocator::deallocate(&$crate::allocator::Global, ptr.cast(), layout);
+        }
+
+        /// Moves the header and body out of the allocation behind `ptr` into a new `HeaderVec`,
+        /// leaving the allocation itself to the weak references.
+        unsafe fn move_out<H, T>(
+            ptr: core::ptr::NonNull<CountedPair<H, T>>,
+        ) -> $crate::vec::HeaderVec<H, T> {
+            let len = counts(ptr).len;
+            let pair_ptr = core::ptr::addr_of_mut!((*ptr.as_ptr()).1);
+            let head = core::ptr::read(core::ptr::addr_of!((*pair_ptr).0));
+            let body_ptr = core::ptr::addr_of_mut!((*pair_ptr).1) as *mut T;
+            $crate::vec::HeaderVec::copy_from_ptr_unsafe(head, body_ptr, len)
+        }
+
+        impl<H, T> $Rc<H, T> {
+            fn counts(&self) -> &$crate::counted::Counts<$Count> {
+                counts(self.ptr)
+            }
+
+            /// Returns a pointer to a `HeaderSlice` representing this value.
+            pub fn as_ptr(this: &Self) -> core::ptr::NonNull<$crate::slice::HeaderSlice<H, T>> {
+                let pair_ptr = unsafe { core::ptr::addr_of_mut!((*this.ptr.as_ptr()).1) };
+                let pair_ptr = unsafe { core::ptr::NonNull::new_unchecked(pair_ptr) };
+                $crate::pair::pair_as_slice_ptr(pair_ptr.cast(), this.counts().len)
+            }
+
+            /// Moves the contents of a `HeaderVec` into a new reference-counted allocation,
+            /// reusing the vector's allocation when the alignment allows it.
+            pub fn from_header_vec(mut src: $crate::vec::HeaderVec<H, T>) -> Self {
+                src.shrink_to_fit();
+                let (ptr, len, cap) = src.into_raw_parts();
+                let (layout, offset) = layout_and_offset::<H, T>(len);
+                let bytes_ptr = unsafe {
+                    $crate::utils::realloc_shifted(
+                        &$crate::allocator::Global,
+                        ptr.as_ptr() as *mut u8,
+                        $crate::slice::HeaderSlice::<H, T>::layout_for_len(cap),
+                        0,
+                        layout,
+                        offset,
+                        $crate::slice::HeaderSlice::<H, T>::layout_for_len(len).size(),
+                    )
+                };
+                let ptr = bytes_ptr as *mut CountedPair<H, T>;
+                let counts = $crate::counted::Counts {
+                    strong: $crate::counted::RefCount::new(1),
+                    weak: $crate::counted::RefCount::new(1),
+                    len,
+                };
+                unsafe { core::ptr::write(core::ptr::addr_of_mut!((*ptr).0), counts) };
+                Self {
+                    ptr: unsafe { core::ptr::NonNull::new_unchecked(ptr) },
+                    _marker: core::marker::PhantomData,
+                }
+            }
+
+            /// Creates a new instance from the given header and iterator.
+            pub fn from_iter<I: IntoIterator<Item = T>>(head: H, iter: I) -> Self {
+                Self::from_header_vec($crate::vec::HeaderVec::from_iter(head, iter))
+            }
+
+            /// Moves the contents of a boxed `HeaderSlice` into a new reference-counted
+            /// allocation.
+            pub fn from_box(src: alloc::boxed::Box<$crate::slice::HeaderSlice<H, T>>) -> Self {
+                Self::from_header_vec($crate::vec::HeaderVec::from_box(src))
+            }
+
+            /// Creates a new weak reference to this value.
+            pub fn downgrade(this: &Self) -> $Weak<H, T> {
+                this.counts().weak.increment();
+                $Weak {
+                    ptr: this.ptr,
+                    _marker: core::marker::PhantomData,
+                }
+            }
+
+            /// The number of strong references to this value.
+            pub fn strong_count(this: &Self) -> usize {
+                this.counts().strong.get()
+            }
+
+            /// The number of weak references to this value.
+            pub fn weak_count(this: &Self) -> usize {
+                this.counts().weak.get() - 1
+            }
+
+            /// Returns true if both references point to the same allocation.
+            pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+                this.ptr == other.ptr
+            }
+
+            /// Returns true if there are no other strong or weak references to this value.
+            fn is_unique(this: &Self) -> bool {
+                this.counts().weak.get() == 1 && this.counts().strong.get() == 1
+            }
+
+            /// Returns a mutable reference to the value if there are no other strong or weak
+            /// references to it.
+            pub fn get_mut(this: &mut Self) -> Option<&mut $crate::slice::HeaderSlice<H, T>> {
+                if Self::is_unique(this) {
+                    Some(unsafe { &mut *Self::as_ptr(this).as_ptr() })
+                } else {
+                    None
+                }
+            }
+
+            /// Returns the contents as a `HeaderVec` if this is the only strong reference.
+            /// Otherwise, returns this reference unchanged.
+            pub fn try_unwrap(this: Self) -> Result<$crate::vec::HeaderVec<H, T>, Self> {
+                if !this.counts().strong.replace_if(1, 0) {
+                    return Err(this);
+                }
+                let ptr = this.ptr;
+                core::mem::forget(this);
+                let len = counts(ptr).len;
+                let (layout, offset) = layout_and_offset::<H, T>(len);
+
+                if counts(ptr).weak.get() == 1 {
+                    // no weak references exist, so the allocation can be handed to the vector
+                    let bytes_ptr = unsafe {
+                        $crate::utils::realloc_shifted(
+                            &$crate::allocator::Global,
+                            ptr.as_ptr() as *mut u8,
+                            layout,
+                            offset,
+                            $crate::slice::HeaderSlice::<H, T>::layout_for_len(len),
+                            0,
+                            $crate::slice::HeaderSlice::<H, T>::layout_for_len(len).size(),
+                        )
+                    };
+                    let vec_ptr = unsafe { core::ptr::NonNull::new_unchecked(bytes_ptr as *mut _) };
+                    return Ok(unsafe { $crate::vec::HeaderVec::from_raw_parts(vec_ptr, len, len) });
+                }
+
+                // weak references keep the allocation alive, so move the contents out of it
+                let vec = unsafe { move_out(ptr) };
+                core::mem::drop($Weak::<H, T> {
+                    ptr,
+                    _marker: core::marker::PhantomData,
+                });
+                Ok(vec)
+            }
+        }
+
+        impl<H: Clone, T: Clone> $Rc<H, T> {
+            /// Returns a mutable reference to the value, first cloning it into a new allocation
+            /// if there are other strong references to it.
+            /// If only weak references remain, the value is moved into a new allocation instead
+            /// and the weak references can no longer be upgraded.
+            pub fn make_mut(this: &mut Self) -> &mut $crate::slice::HeaderSlice<H, T> {
+                if this.counts().strong.replace_if(1, 0) {
+                    if this.counts().weak.get() == 1 {
+                        // there are no weak references either, so the value is already unique
+                        this.counts().strong.replace_if(0, 1);
+                    } else {
+                        let weak = $Weak::<H, T> {
+                            ptr: this.ptr,
+                            _marker: core::marker::PhantomData,
+                        };
+                        let vec = unsafe { move_out(this.ptr) };
+                        unsafe { core::ptr::write(this, Self::from_header_vec(vec)) };
+                        core::mem::drop(weak);
+                    }
+                } else {
+                    let owned = alloc::borrow::ToOwned::to_owned(&**this);
+                    *this = Self::from_header_vec(owned);
+                }
+                unsafe { &mut *Self::as_ptr(this).as_ptr() }
+            }
+        }
+
+        impl<H, T> core::ops::Deref for $Rc<H, T> {
+            type Target = $crate::slice::HeaderSlice<H, T>;
+            fn deref(&self) -> &Self::Target {
+                unsafe { &*Self::as_ptr(self).as_ptr() }
+            }
+        }
+
+        impl<H, T> AsRef<$crate::slice::HeaderSlice<H, T>> for $Rc<H, T> {
+            fn as_ref(&self) -> &$crate::slice::HeaderSlice<H, T> {
+                self
+            }
+        }
+
+        impl<H, T> core::borrow::Borrow<$crate::slice::HeaderSlice<H, T>> for $Rc<H, T> {
+            fn borrow(&self) -> &$crate::slice::HeaderSlice<H, T> {
+                self
+            }
+        }
+
+        impl<H, T> Clone for $Rc<H, T> {
+            fn clone(&self) -> Self {
+                self.counts().strong.increment();
+                Self {
+                    ptr: self.ptr,
+                    _marker: core::marker::PhantomData,
+                }
+            }
+        }
+
+        impl<H, T> Drop for $Rc<H, T> {
+            fn drop(&mut self) {
+                if self.counts().strong.decrement() != 1 {
+                    return;
+                }
+                unsafe { core::ptr::drop_in_place(Self::as_ptr(self).as_ptr()) };
+                // release the weak reference shared by the strong references
+                core::mem::drop($Weak::<H, T> {
+                    ptr: self.ptr,
+                    _marker: core::marker::PhantomData,
+                });
+            }
+        }
+
+        impl<H, T, Rhs: ?Sized> PartialEq<Rhs> for $Rc<H, T>
+        where
+            H: PartialEq,
+            T: PartialEq,
+            Rhs: core::borrow::Borrow<$crate::slice::HeaderSlice<H, T>>,
+        {
+            fn eq(&self, rhs: &Rhs) -> bool {
+                **self == *rhs.borrow()
+            }
+        }
+
+        impl<H: Eq, T: Eq> Eq for $Rc<H, T> {}
+
+        impl<H, T, Rhs: ?Sized> PartialOrd<Rhs> for $Rc<H, T>
+        where
+            H: PartialOrd,
+            T: PartialOrd,
+            Rhs: core::borrow::Borrow<$crate::slice::HeaderSlice<H, T>>,
+        {
+            fn partial_cmp(&self, rhs: &Rhs) -> Option<core::cmp::Ordering> {
+                (**self).partial_cmp(rhs.borrow())
+            }
+        }
+
+        impl<H: Ord, T: Ord> Ord for $Rc<H, T> {
+            fn cmp(&self, rhs: &Self) -> core::cmp::Ordering {
+                (**self).cmp(&**rhs)
+            }
+        }
+
+        impl<H: core::hash::Hash, T: core::hash::Hash> core::hash::Hash for $Rc<H, T> {
+            fn hash<S: core::hash::Hasher>(&self, state: &mut S) {
+                (**self).hash(state)
+            }
+        }
+
+        impl<H: core::fmt::Debug, T: core::fmt::Debug> core::fmt::Debug for $Rc<H, T> {
+            fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+                (**self).fmt(f)
+            }
+        }
+
+        impl<H: Default, T> core::iter::FromIterator<T> for $Rc<H, T> {
+            fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+                Self::from_iter(H::default(), iter)
+            }
+        }
+
+        impl<H, T> From<$crate::vec::HeaderVec<H, T>> for $Rc<H, T> {
+            fn from(src: $crate::vec::HeaderVec<H, T>) -> Self {
+                Self::from_header_vec(src)
+            }
+        }
+
+        impl<H, T> From<alloc::boxed::Box<$crate::slice::HeaderSlice<H, T>>> for $Rc<H, T> {
+            fn from(src: alloc::boxed::Box<$crate::slice::HeaderSlice<H, T>>) -> Self {
+                Self::from_box(src)
+            }
+        }
+
+        impl<H, T> $Weak<H, T> {
+            /// Returns a strong reference to the value if it hasn't been dropped yet.
+            pub fn upgrade(&self) -> Option<$Rc<H, T>> {
+                if !counts(self.ptr).strong.increment_if_nonzero() {
+                    return None;
+                }
+                Some($Rc {
+                    ptr: self.ptr,
+                    _marker: core::marker::PhantomData,
+                })
+            }
+
+            /// The number of strong references to the value.
+            pub fn strong_count(&self) -> usize {
+                counts(self.ptr).strong.get()
+            }
+
+            /// The number of weak references to the value, or zero if it has been dropped.
+            pub fn weak_count(&self) -> usize {
+                if counts(self.ptr).strong.get() == 0 {
+                    0
+                } else {
+                    counts(self.ptr).weak.get() - 1
+                }
+            }
+
+            /// Returns true if both references point to the same allocation.
+            pub fn ptr_eq(&self, other: &Self) -> bool {
+                self.ptr == other.ptr
+            }
+        }
+
+        impl<H, T> Clone for $Weak<H, T> {
+            fn clone(&self) -> Self {
+                counts(self.ptr).weak.increment();
+                Self {
+                    ptr: self.ptr,
+                    _marker: core::marker::PhantomData,
+                }
+            }
+        }
+
+        impl<H, T> Drop for $Weak<H, T> {
+            fn drop(&mut self) {
+                if counts(self.ptr).weak.decrement() == 1 {
+                    unsafe { dealloc(self.ptr) };
+                }
+            }
+        }
+
+        impl<H, T> core::fmt::Debug for $Weak<H, T> {
+            fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+                f.write_str("(Weak)")
+            }
+        }
+    };
+}
@@ -0,0 +1,690 @@
+use crate::slice::HeaderSlice;
+use crate::utils;
+use crate::vec::HeaderVec;
+use core::cmp::Ordering;
+use core::fmt::{self, Debug};
+use core::hash::{self, Hash};
+use core::iter::{self, FusedIterator};
+use core::marker::PhantomData;
+use core::mem;
+use core::ops::{Index, IndexMut, RangeBounds};
+use core::ptr::{self, NonNull};
+use core::slice;
+
+/// A double-ended queue with a header, stored as a ring buffer in the same allocation as the
+/// header, like a `HeaderVec`.
+pub struct HeaderVecDeque<H, T> {
+    /// Holds the header and allocation. Its length is always 0; the elements are tracked by
+    /// `start` and `len` instead.
+    buf: HeaderVec<H, T>,
+    /// The physical index of the first element.
+    start: usize,
+    len: usize,
+}
+
+impl<H, T> HeaderVecDeque<H, T> {
+    /// Creates an empty deque with the given header.
+    pub fn new(head: H) -> Self {
+        Self::from(HeaderVec::new(head))
+    }
+
+    /// Creates an empty deque with the given header that can hold at least `cap` elements
+    /// without reallocating.
+    pub fn with_capacity(head: H, cap: usize) -> Self {
+        Self::from(HeaderVec::with_capacity(head, cap))
+    }
+
+    /// Creates a deque from the given header and iterator.
+    pub fn from_iter<I: IntoIterator<Item = T>>(head: H, iter: I) -> Self {
+        Self::from(HeaderVec::from_iter(head, iter))
+    }
+
+    /// Converts the deque into a `HeaderVec`, reusing its allocation.
+    pub fn into_header_vec(mut self) -> HeaderVec<H, T> {
+        self.make_contiguous();
+        let len = self.len;
+        let mut this = mem::ManuallyDrop::new(self);
+        let (ptr, _, cap) = this.buf.as_raw_parts();
+        unsafe { HeaderVec::from_raw_parts(ptr, len, cap) }
+    }
+
+    /// Returns a reference to the header.
+    pub fn head(&self) -> &H {
+        &self.buf.head
+    }
+
+    /// Returns a mutable reference to the header.
+    pub fn head_mut(&mut self) -> &mut H {
+        &mut self.buf.head
+    }
+
+    /// The number of elements in the deque.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the deque has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The total reserved capacity of the deque.
+    pub fn capacity(&self) -> usize {
+        self.buf.capacity()
+    }
+
+    /// Returns a pointer to the first slot of the ring buffer.
+    fn ptr(&self) -> *mut T {
+        unsafe { ptr::addr_of_mut!((*self.buf.as_ptr().as_ptr()).body) as *mut T }
+    }
+
+    /// Converts an index relative to the front of the deque to an index into the ring buffer.
+    fn to_physical(&self, idx: usize) -> usize {
+        let cap = self.capacity();
+        if idx >= cap - self.start {
+            idx - (cap - self.start)
+        } else {
+            self.start + idx
+        }
+    }
+
+    /// Returns a pointer to the slot at `idx`, relative to the front of the deque.
+    fn slot(&self, idx: usize) -> *mut T {
+        unsafe { self.ptr().add(self.to_physical(idx)) }
+    }
+
+    /// Returns the slots in `start..end`, relative to the front of the deque, as up to two
+    /// contiguous slices.
+    fn slice_ptrs(&self, start: usize, end: usize) -> (*mut [T], *mut [T]) {
+        let len = end - start;
+        let first = self.to_physical(start);
+        let first_len = len.min(self.capacity() - first);
+        unsafe {
+            (
+                ptr::slice_from_raw_parts_mut(self.ptr().add(first), first_len),
+                ptr::slice_from_raw_parts_mut(self.ptr(), len - first_len),
+            )
+        }
+    }
+
+    /// Returns the elements in order as up to two contiguous slices.
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        let (a, b) = self.slice_ptrs(0, self.len);
+        unsafe { (&*a, &*b) }
+    }
+
+    /// Returns the elements in order as up to two contiguous mutable slices.
+    pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+        let (a, b) = self.slice_ptrs(0, self.len);
+        unsafe { (&mut *a, &mut *b) }
+    }
+
+    /// Moves the elements so they directly follow the header in order, and returns them along
+    /// with the header as a `HeaderSlice`.
+    pub fn make_contiguous(&mut self) -> &mut HeaderSlice<H, T> {
+        let cap = self.capacity();
+        let base = self.ptr();
+        if self.start != 0 {
+            unsafe {
+                if self.len <= cap - self.start {
+                    ptr::copy(base.add(self.start), base, self.len);
+                } else {
+                    // move the front part down so it directly follows the back part, then
+                    // rotate them into order
+                    let front_len = cap - self.start;
+                    let back_len = self.len - front_len;
+                    ptr::copy(base.add(self.start), base.add(back_len), front_len);
+                    slice::from_raw_parts_mut(base, self.len).rotate_left(back_len);
+                }
+            }
+            self.start = 0;
+        }
+        let hslice = ptr::slice_from_raw_parts_mut(self.buf.as_ptr().as_ptr() as *mut T, self.len)
+            as *mut HeaderSlice<H, T>;
+        unsafe { &mut *hslice }
+    }
+
+    /// Makes sure there is room for at least `additional` more elements.
+    pub fn reserve(&mut self, additional: usize) {
+        let new_len = self.len.checked_add(additional).expect("capacity overflow");
+        let old_cap = self.capacity();
+        if new_len <= old_cap {
+            return;
+        }
+        self.buf.reserve(new_len);
+        self.handle_capacity_increase(old_cap);
+    }
+
+    /// Moves the elements so they are in order again after the ring buffer has grown from
+    /// `old_cap`.
+    fn handle_capacity_increase(&mut self, old_cap: usize) {
+        let new_cap = self.capacity();
+        if self.start <= old_cap - self.len {
+            // the elements were already contiguous
+            return;
+        }
+        let front_len = old_cap - self.start;
+        let back_len = self.len - front_len;
+        let base = self.ptr();
+        unsafe {
+            if back_len < front_len && back_len <= new_cap - old_cap {
+                // move the back part to just after the old capacity
+                ptr::copy_nonoverlapping(base, base.add(old_cap), back_len);
+            } else {
+                // move the front part to the end of the new capacity
+                let new_start = new_cap - front_len;
+                ptr::copy(base.add(self.start), base.add(new_start), front_len);
+                self.start = new_start;
+            }
+        }
+    }
+
+    fn grow_if_full(&mut self) {
+        if self.len == self.capacity() {
+            self.reserve(1);
+        }
+    }
+
+    /// Adds a value to the back of the deque.
+    pub fn push_back(&mut self, val: T) {
+        self.grow_if_full();
+        unsafe { self.slot(self.len).write(val) };
+        self.len += 1;
+    }
+
+    /// Adds a value to the front of the deque.
+    pub fn push_front(&mut self, val: T) {
+        self.grow_if_full();
+        self.start = self.to_physical(self.capacity() - 1);
+        self.len += 1;
+        unsafe { self.ptr().add(self.start).write(val) };
+    }
+
+    /// Removes the last value from the deque and returns it, or `None` if it is empty.
+    pub fn pop_back(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        Some(unsafe { self.slot(self.len).read() })
+    }
+
+    /// Removes the first value from the deque and returns it, or `None` if it is empty.
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        let val = unsafe { self.slot(0).read() };
+        self.start = self.to_physical(1);
+        self.len -= 1;
+        Some(val)
+    }
+
+    /// Returns a reference to the element at `index`, if it exists.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index < self.len {
+            Some(unsafe { &*self.slot(index) })
+        } else {
+            None
+        }
+    }
+
+    /// Returns a mutable reference to the element at `index`, if it exists.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index < self.len {
+            Some(unsafe { &mut *self.slot(index) })
+        } else {
+            None
+        }
+    }
+
+    /// Returns a reference to the first element, if it exists.
+    pub fn front(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    /// Returns a mutable reference to the first element, if it exists.
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        self.get_mut(0)
+    }
+
+    /// Returns a reference to the last element, if it exists.
+    pub fn back(&self) -> Option<&T> {
+        self.get(self.len.wrapping_sub(1))
+    }
+
+    /// Returns a mutable reference to the last element, if it exists.
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        self.get_mut(self.len.wrapping_sub(1))
+    }
+
+    /// Drops all elements after `new_len`.
+    /// Panics if `new_len > len`.
+    pub fn truncate(&mut self, new_len: usize) {
+        assert!(new_len <= self.len);
+        let (a, b) = self.slice_ptrs(new_len, self.len);
+        self.len = new_len;
+        let _back = DropSlice(b);
+        unsafe { ptr::drop_in_place(a) };
+    }
+
+    /// Drops all elements in the deque, keeping the allocation.
+    pub fn clear(&mut self) {
+        self.truncate(0);
+        self.start = 0;
+    }
+
+    /// Returns an iterator over the elements from front to back.
+    pub fn iter(&self) -> Iter<'_, T> {
+        let (a, b) = self.as_slices();
+        Iter {
+            front: a.iter(),
+            back: b.iter(),
+        }
+    }
+
+    /// Returns an iterator over mutable references to the elements from front to back.
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        let (a, b) = self.as_mut_slices();
+        IterMut {
+            front: a.iter_mut(),
+            back: b.iter_mut(),
+        }
+    }
+
+    /// Removes the elements in `range` and returns them as an iterator. Elements that aren't
+    /// consumed are dropped when the iterator is dropped.
+    /// Panics if the range is out of bounds.
+    pub fn drain<R: RangeBounds<usize>>(&mut self, range: R) -> Drain<'_, H, T> {
+        let range = utils::slice_range(range, self.len);
+        Drain::new(self, range.start, range.end)
+    }
+}
+
+/// Drops the elements of a slice when it goes out of scope, so they are dropped even if dropping
+/// an earlier slice panics.
+struct DropSlice<T>(*mut [T]);
+
+impl<T> Drop for DropSlice<T> {
+    fn drop(&mut self) {
+        unsafe { ptr::drop_in_place(self.0) }
+    }
+}
+
+impl<H, T> Drop for HeaderVecDeque<H, T> {
+    fn drop(&mut self) {
+        // the header and allocation are dropped with `buf`
+        let (a, b) = self.slice_ptrs(0, self.len);
+        let _back = DropSlice(b);
+        unsafe { ptr::drop_in_place(a) };
+    }
+}
+
+impl<H, T> From<HeaderVec<H, T>> for HeaderVecDeque<H, T> {
+    /// Converts a `HeaderVec` into a deque, reusing its allocation.
+    fn from(src: HeaderVec<H, T>) -> Self {
+        let (ptr, len, cap) = src.into_raw_parts();
+        Self {
+            buf: unsafe { HeaderVec::from_raw_parts(ptr, 0, cap) },
+            start: 0,
+            len,
+        }
+    }
+}
+
+impl<H, T> From<HeaderVecDeque<H, T>> for HeaderVec<H, T> {
+    fn from(src: HeaderVecDeque<H, T>) -> Self {
+        src.into_header_vec()
+    }
+}
+
+impl<H, T> Index<usize> for HeaderVecDeque<H, T> {
+    type Output = T;
+    fn index(&self, index: usize) -> &T {
+        self.get(index).expect("index out of bounds")
+    }
+}
+
+impl<H, T> IndexMut<usize> for HeaderVecDeque<H, T> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        self.get_mut(index).expect("index out of bounds")
+    }
+}
+
+impl<H: Clone, T: Clone> Clone for HeaderVecDeque<H, T> {
+    fn clone(&self) -> Self {
+        let mut out = Self::with_capacity(self.head().clone(), self.len);
+        out.extend(self.iter().cloned());
+        out
+    }
+}
+
+impl<H, T> Extend<T> for HeaderVecDeque<H, T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for x in iter {
+            self.push_back(x);
+        }
+    }
+}
+
+impl<H: PartialEq, T: PartialEq> PartialEq for HeaderVecDeque<H, T> {
+    fn eq(&self, rhs: &Self) -> bool {
+        self.head() == rhs.head() && self.len == rhs.len && self.iter().eq(rhs.iter())
+    }
+}
+
+impl<H: Eq, T: Eq> Eq for HeaderVecDeque<H, T> {}
+
+impl<H: PartialOrd, T: PartialOrd> PartialOrd for HeaderVecDeque<H, T> {
+    fn partial_cmp(&self, rhs: &Self) -> Option<Ordering> {
+        match self.head().partial_cmp(rhs.head()) {
+            Some(Ordering::Equal) => self.iter().partial_cmp(rhs.iter()),
+            ord => ord,
+        }
+    }
+}
+
+impl<H: Ord, T: Ord> Ord for HeaderVecDeque<H, T> {
+    fn cmp(&self, rhs: &Self) -> Ordering {
+        self.head()
+            .cmp(rhs.head())
+            .then_with(|| self.iter().cmp(rhs.iter()))
+    }
+}
+
+impl<H: Hash, T: Hash> Hash for HeaderVecDeque<H, T> {
+    fn hash<S: hash::Hasher>(&self, state: &mut S) {
+        // hash the elements one at a time after the length, like `VecDeque`, so the result doesn't
+        // depend on where the ring buffer wraps
+        self.head().hash(state);
+        state.write_usize(self.len);
+        self.iter().for_each(|x| x.hash(state));
+    }
+}
+
+impl<H: Debug, T: Debug> Debug for HeaderVecDeque<H, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("[")?;
+        self.head().fmt(f)?;
+        let mut iter = self.iter();
+        match iter.next() {
+            None => f.write_str(";]"),
+            Some(first) => {
+                f.write_str("; ")?;
+                first.fmt(f)?;
+                for item in iter {
+                    f.write_str(", ")?;
+                    item.fmt(f)?;
+                }
+                f.write_str("]")
+            }
+        }
+    }
+}
+
+impl<H: Default, T> Default for HeaderVecDeque<H, T> {
+    fn default() -> Self {
+        Self::new(H::default())
+    }
+}
+
+impl<H: Default, T> iter::FromIterator<T> for HeaderVecDeque<H, T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::from_iter(H::default(), iter)
+    }
+}
+
+impl<H, T> IntoIterator for HeaderVecDeque<H, T> {
+    type Item = T;
+    type IntoIter = IntoIter<H, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { deque: self }
+    }
+}
+
+impl<'a, H, T> IntoIterator for &'a HeaderVecDeque<H, T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, H, T> IntoIterator for &'a mut HeaderVecDeque<H, T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+macro_rules! deque_iter_impls {
+    ($Iter:ident, $Item:ty) => {
+        impl<'a, T> Iterator for $Iter<'a, T> {
+            type Item = $Item;
+            fn next(&mut self) -> Option<Self::Item> {
+                match self.front.next() {
+                    Some(x) => Some(x),
+                    None => {
+                        mem::swap(&mut self.front, &mut self.back);
+                        self.front.next()
+                    }
+                }
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                let len = self.len();
+                (len, Some(len))
+            }
+        }
+
+        impl<'a, T> DoubleEndedIterator for $Iter<'a, T> {
+            fn next_back(&mut self) -> Option<Self::Item> {
+                match self.back.next_back() {
+                    Some(x) => Some(x),
+                    None => {
+                        mem::swap(&mut self.front, &mut self.back);
+                        self.back.next_back()
+                    }
+                }
+            }
+        }
+
+        impl<'a, T> ExactSizeIterator for $Iter<'a, T> {
+            fn len(&self) -> usize {
+                self.front.len() + self.back.len()
+            }
+        }
+
+        impl<'a, T> FusedIterator for $Iter<'a, T> {}
+    };
+}
+
+/// An iterator over references to the elements of a `HeaderVecDeque`.
+#[derive(Clone)]
+pub struct Iter<'a, T> {
+    front: slice::Iter<'a, T>,
+    back: slice::Iter<'a, T>,
+}
+
+deque_iter_impls!(Iter, &'a T);
+
+impl<T: Debug> Debug for Iter<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Iter")
+            .field(&self.front.as_slice())
+            .field(&self.back.as_slice())
+            .finish()
+    }
+}
+
+/// An iterator over mutable references to the elements of a `HeaderVecDeque`.
+pub struct IterMut<'a, T> {
+    front: slice::IterMut<'a, T>,
+    back: slice::IterMut<'a, T>,
+}
+
+deque_iter_impls!(IterMut, &'a mut T);
+
+impl<T: Debug> Debug for IterMut<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("IterMut")
+            .field(&self.front.as_slice())
+            .field(&self.back.as_slice())
+            .finish()
+    }
+}
+
+/// An iterator that moves the elements out of a `HeaderVecDeque`, dropping the header along with
+/// any remaining elements.
+pub struct IntoIter<H, T> {
+    deque: HeaderVecDeque<H, T>,
+}
+
+impl<H, T> IntoIter<H, T> {
+    /// Returns a reference to the header.
+    pub fn head(&self) -> &H {
+        self.deque.head()
+    }
+}
+
+impl<H, T> Iterator for IntoIter<H, T> {
+    type Item = T;
+    fn next(&mut self) -> Option<T> {
+        self.deque.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.deque.len, Some(self.deque.len))
+    }
+}
+
+impl<H, T> DoubleEndedIterator for IntoIter<H, T> {
+    fn next_back(&mut self) -> Option<T> {
+        self.deque.pop_back()
+    }
+}
+
+impl<H, T> ExactSizeIterator for IntoIter<H, T> {}
+
+impl<H, T> FusedIterator for IntoIter<H, T> {}
+
+impl<H: Debug, T: Debug> Debug for IntoIter<H, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("IntoIter").field(&self.deque).finish()
+    }
+}
+
+/// An iterator that removes a range of elements from a `HeaderVecDeque`.
+/// Created by `HeaderVecDeque::drain`.
+pub struct Drain<'a, H, T> {
+    deque: NonNull<HeaderVecDeque<H, T>>,
+    /// The index of the next element to yield from the front.
+    idx: usize,
+    /// One past the index of the next element to yield from the back.
+    end: usize,
+    drain_start: usize,
+    drain_end: usize,
+    orig_len: usize,
+    _marker: PhantomData<&'a mut HeaderVecDeque<H, T>>,
+}
+
+impl<'a, H, T> Drain<'a, H, T> {
+    fn new(deque: &'a mut HeaderVecDeque<H, T>, start: usize, end: usize) -> Self {
+        let orig_len = deque.len;
+        // only the elements before the range are owned by the deque while the iterator is
+        // alive, so the rest are leaked if the iterator is forgotten
+        deque.len = start;
+        Self {
+            deque: NonNull::from(deque),
+            idx: start,
+            end,
+            drain_start: start,
+            drain_end: end,
+            orig_len,
+            _marker: PhantomData,
+        }
+    }
+
+    fn deque(&self) -> &HeaderVecDeque<H, T> {
+        unsafe { self.deque.as_ref() }
+    }
+}
+
+impl<H, T> Iterator for Drain<'_, H, T> {
+    type Item = T;
+    fn next(&mut self) -> Option<T> {
+        if self.idx == self.end {
+            return None;
+        }
+        let val = unsafe { self.deque().slot(self.idx).read() };
+        self.idx += 1;
+        Some(val)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.idx;
+        (len, Some(len))
+    }
+}
+
+impl<H, T> DoubleEndedIterator for Drain<'_, H, T> {
+    fn next_back(&mut self) -> Option<T> {
+        if self.idx == self.end {
+            return None;
+        }
+        self.end -= 1;
+        Some(unsafe { self.deque().slot(self.end).read() })
+    }
+}
+
+impl<H, T> ExactSizeIterator for Drain<'_, H, T> {}
+
+impl<H, T> FusedIterator for Drain<'_, H, T> {}
+
+impl<H, T> Drop for Drain<'_, H, T> {
+    fn drop(&mut self) {
+        /// Closes the gap left by the drained range, even if dropping an element panics.
+        struct DropGuard<'r, 'a, H, T>(&'r mut Drain<'a, H, T>);
+
+        impl<H, T> Drop for DropGuard<'_, '_, H, T> {
+            fn drop(&mut self) {
+                let drain = &mut *self.0;
+                let deque = unsafe { drain.deque.as_mut() };
+                let front_len = drain.drain_start;
+                let back_len = drain.orig_len - drain.drain_end;
+                let gap = drain.drain_end - drain.drain_start;
+                unsafe {
+                    // move whichever side of the gap is shorter
+                    if front_len < back_len {
+                        for i in (0..front_len).rev() {
+                            ptr::copy(deque.slot(i), deque.slot(i + gap), 1);
+                        }
+                        deque.start = deque.to_physical(gap);
+                    } else {
+                        for i in 0..back_len {
+                            let src = deque.slot(drain.drain_end + i);
+                            ptr::copy(src, deque.slot(drain.drain_start + i), 1);
+                        }
+                    }
+                }
+                deque.len = drain.orig_len - gap;
+            }
+        }
+
+        let (a, b) = self.deque().slice_ptrs(self.idx, self.end);
+        self.idx = self.end;
+        let _guard = DropGuard(self);
+        let _back = DropSlice(b);
+        unsafe { ptr::drop_in_place(a) };
+    }
+}
+
+impl<H, T: Debug> Debug for Drain<'_, H, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (a, b) = self.deque().slice_ptrs(self.idx, self.end);
+        unsafe { f.debug_tuple("Drain").field(&&*a).field(&&*b).finish() }
+    }
+}
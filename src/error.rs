@@ -0,0 +1,91 @@
+#[cfg(feature = "alloc")]
+use alloc::alloc::{handle_alloc_error, Layout};
+use core::fmt;
+
+/// The error returned when a `HeaderVec` fails to reserve capacity.
+#[cfg(feature = "alloc")]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum TryReserveError {
+    /// The requested capacity does not fit in a `usize`.
+    CapacityOverflow,
+    /// The memory layout for the requested capacity exceeds `isize::MAX` bytes.
+    LayoutError,
+    /// The allocator failed to allocate memory with the given layout.
+    AllocError { layout: Layout },
+}
+
+#[cfg(feature = "alloc")]
+impl fmt::Display for TryReserveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("memory allocation failed")?;
+        match self {
+            Self::CapacityOverflow => f.write_str(" because the computed capacity overflowed"),
+            Self::LayoutError => f.write_str(" because the memory layout was too large"),
+            Self::AllocError { .. } => f.write_str(" because the allocator returned an error"),
+        }
+    }
+}
+
+/// The error returned when an `ArrayHeaderVec` is full. Holds the value that could not be
+/// added.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct CapacityError<T = ()> {
+    element: T,
+}
+
+impl<T> CapacityError<T> {
+    pub(crate) const fn new(element: T) -> Self {
+        Self { element }
+    }
+
+    /// Returns the value that could not be added.
+    pub fn into_inner(self) -> T {
+        self.element
+    }
+
+    /// Discards the value that could not be added.
+    pub fn simplify(self) -> CapacityError {
+        CapacityError::new(())
+    }
+}
+
+impl<T> fmt::Display for CapacityError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("insufficient capacity")
+    }
+}
+
+/// The error returned when a byte slice can't be cast to a `HeaderSlice`.
+#[cfg(feature = "bytemuck")]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CastError {
+    /// The byte slice is not aligned for the `HeaderSlice`.
+    Misaligned,
+    /// The byte slice is too short to hold the header.
+    TooShort,
+    /// The bytes after the header don't make up a whole number of elements, or don't end with the
+    /// trailing padding of the `HeaderSlice`.
+    InvalidLength,
+}
+
+#[cfg(feature = "bytemuck")]
+impl fmt::Display for CastError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::Misaligned => "byte slice is not aligned for the header slice",
+            Self::TooShort => "byte slice is too short to hold the header",
+            Self::InvalidLength => "byte slice length is not a valid header slice size",
+        })
+    }
+}
+
+/// Unwraps the result of a fallible allocation, calling `handle_alloc_error` if the allocator
+/// failed or panicking if the capacity was too large.
+#[cfg(feature = "alloc")]
+pub(crate) fn handle_reserve<R>(result: Result<R, TryReserveError>) -> R {
+    match result {
+        Ok(x) => x,
+        Err(TryReserveError::AllocError { layout }) => handle_alloc_error(layout),
+        Err(_) => panic!("capacity overflow"),
+    }
+}
@@ -0,0 +1,54 @@
+//! Capacity management for `HeaderVec`.
+//!
+//! A `HeaderVec` asks its `GrowthPolicy` how much to reserve whenever it runs out of room, and
+//! whether to give memory back after elements are removed. Explicit requests like `reserve_exact`,
+//! `shrink_to_fit` and `shrink_to` always do exactly what they say, regardless of the policy.
+
+use crate::vec::MIN_CAP;
+
+/// Decides how a `HeaderVec` grows and shrinks.
+pub trait GrowthPolicy {
+    /// Returns the capacity a vector with capacity `cap` should grow to so it can hold `required`
+    /// elements. The result must be at least `required`.
+    fn grow(cap: usize, required: usize) -> usize;
+
+    /// Returns the capacity a vector with capacity `cap` should shrink to now that it holds `len`
+    /// elements, or `None` to keep its current capacity. The result must be at least `len`.
+    fn shrink(cap: usize, len: usize) -> Option<usize>;
+}
+
+/// Doubles the capacity when growing, and halves it once no more than a quarter is in use.
+///
+/// The gap between the two thresholds means alternating pushes and pops never reallocate on every
+/// call, so both are amortized O(1).
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct DefaultGrowth;
+
+impl GrowthPolicy for DefaultGrowth {
+    fn grow(cap: usize, required: usize) -> usize {
+        cap.saturating_mul(2).max(required).max(MIN_CAP)
+    }
+
+    fn shrink(cap: usize, len: usize) -> Option<usize> {
+        if cap > MIN_CAP && len <= cap / 4 {
+            Some(len.saturating_mul(2).max(MIN_CAP))
+        } else {
+            None
+        }
+    }
+}
+
+/// Grows like `DefaultGrowth`, but never shrinks automatically. Use `shrink_to` or
+/// `shrink_to_fit` to release memory.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct NeverShrink;
+
+impl GrowthPolicy for NeverShrink {
+    fn grow(cap: usize, required: usize) -> usize {
+        DefaultGrowth::grow(cap, required)
+    }
+
+    fn shrink(_cap: usize, _len: usize) -> Option<usize> {
+        None
+    }
+}
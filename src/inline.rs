@@ -0,0 +1,50 @@
+use crate::slice::HeaderSlice;
+use core::mem::MaybeUninit;
+use core::ptr;
+
+/// A header and storage for up to `N` elements, laid out like a `HeaderSlice<H, T>` of length
+/// `N`. Used by the vectors that store their contents inline.
+#[repr(C)]
+pub(crate) struct Inline<H, T, const N: usize> {
+    pub(crate) head: H,
+    pub(crate) body: [MaybeUninit<T>; N],
+}
+
+impl<H, T, const N: usize> Inline<H, T, N> {
+    pub(crate) const fn new(head: H) -> Self {
+        Self {
+            head,
+            body: unsafe { MaybeUninit::<[MaybeUninit<T>; N]>::uninit().assume_init() },
+        }
+    }
+
+    /// Returns a pointer to the first `len` elements of `this` as a `HeaderSlice`.
+    pub(crate) fn slice_ptr(this: *mut Self, len: usize) -> *mut HeaderSlice<H, T> {
+        ptr::slice_from_raw_parts_mut(this as *mut T, len) as *mut HeaderSlice<H, T>
+    }
+
+    /// Returns the header and first `len` elements as a `HeaderSlice`.
+    ///
+    /// # Safety
+    /// The first `len` elements must be initialized.
+    pub(crate) unsafe fn as_header_slice(&self, len: usize) -> &HeaderSlice<H, T> {
+        &*Self::slice_ptr(self as *const Self as *mut Self, len)
+    }
+
+    /// Returns the header and first `len` elements as a mutable `HeaderSlice`.
+    ///
+    /// # Safety
+    /// The first `len` elements must be initialized.
+    pub(crate) unsafe fn as_header_slice_mut(&mut self, len: usize) -> &mut HeaderSlice<H, T> {
+        &mut *Self::slice_ptr(self, len)
+    }
+
+    /// Drops the elements in `start..end`.
+    ///
+    /// # Safety
+    /// The elements must be initialized, and must not be used afterwards.
+    pub(crate) unsafe fn drop_range(&mut self, start: usize, end: usize) {
+        let range = &mut self.body[start..end] as *mut [MaybeUninit<T>] as *mut [T];
+        ptr::drop_in_place(range);
+    }
+}
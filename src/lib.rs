@@ -1,14 +1,75 @@
 #![no_std]
+#[cfg(feature = "alloc")]
 extern crate alloc;
+#[cfg(test)]
+extern crate std;
 
 #[macro_use]
 mod utils;
+#[cfg(feature = "alloc")]
+#[macro_use]
+mod counted;
 
+#[cfg(feature = "alloc")]
+mod allocator;
+#[cfg(feature = "alloc")]
+pub mod arc;
+pub mod array;
+#[cfg(feature = "bytemuck")]
+mod bytes;
+#[cfg(feature = "alloc")]
+pub mod deque;
+pub mod error;
+#[cfg(feature = "alloc")]
+pub mod growth;
+mod inline;
 pub mod pair;
+#[cfg(feature = "alloc")]
+pub mod rc;
+#[cfg(feature = "serde")]
+pub mod serde;
+#[cfg(feature = "alloc")]
+mod shared;
 pub mod slice;
-#[cfg(test)]
+#[cfg(feature = "alloc")]
+pub mod small;
+#[cfg(feature = "alloc")]
+pub mod soa;
+#[cfg(feature = "alloc")]
+pub mod string;
+#[cfg(all(test, feature = "alloc"))]
 mod test;
+#[cfg(feature = "alloc")]
+pub mod thin;
+#[cfg(feature = "alloc")]
 pub mod vec;
+pub mod view;
 
+#[cfg(feature = "alloc")]
+pub use allocator::Global;
+#[cfg(feature = "alloc")]
+pub use arc::HeaderArc;
+pub use array::ArrayHeaderVec;
+#[cfg(feature = "alloc")]
+pub use deque::HeaderVecDeque;
+pub use error::CapacityError;
+#[cfg(feature = "bytemuck")]
+pub use error::CastError;
+#[cfg(feature = "alloc")]
+pub use error::TryReserveError;
+#[cfg(feature = "alloc")]
+pub use growth::{DefaultGrowth, GrowthPolicy, NeverShrink};
+#[cfg(feature = "alloc")]
+pub use rc::HeaderRc;
 pub use slice::HeaderSlice;
+#[cfg(feature = "alloc")]
+pub use small::SmallHeaderVec;
+#[cfg(feature = "alloc")]
+pub use soa::HeaderSoa;
+#[cfg(feature = "alloc")]
+pub use string::{HeaderStr, HeaderString};
+#[cfg(feature = "alloc")]
+pub use thin::ThinHeaderVec;
+#[cfg(feature = "alloc")]
 pub use vec::HeaderVec;
+pub use view::{HeaderSliceMut, HeaderSliceRef};
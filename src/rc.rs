@@ -0,0 +1,10 @@
+use core::cell::Cell;
+
+header_rc! {
+    /// A single-threaded reference-counted `HeaderSlice`, storing its reference counts in the
+    /// same allocation as its header and body.
+    pub struct HeaderRc;
+    /// A weak reference to the contents of a `HeaderRc`.
+    pub struct Weak;
+    count: Cell<usize>;
+}
@@ -0,0 +1,342 @@
+//! `serde` support, enabled with the `serde` feature.
+//!
+//! By default, a `HeaderSlice` is serialized as a 2-tuple `(head, [body...])`. The `as_struct`
+//! and `bytes` modules can be used with `#[serde(with = "...")]` to serialize it as a struct
+//! `{ head, body }`, or as a tuple with the body as a byte string when `T = u8`.
+//!
+//! Deserialization streams the elements straight into a `HeaderVec`, reserving capacity from the
+//! size hint of the input.
+
+use crate::{HeaderSlice, HeaderVec};
+use ::serde::de::{self, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
+use ::serde::ser::{SerializeStruct, SerializeTuple, Serializer};
+use ::serde::{Deserialize, Serialize};
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem;
+use core::ops::Deref;
+
+/// Limits how much is preallocated from an untrusted size hint.
+const MAX_PREALLOC_BYTES: usize = 1024 * 1024;
+
+fn cautious_capacity<T>(hint: Option<usize>) -> usize {
+    let max = MAX_PREALLOC_BYTES / mem::size_of::<T>().max(1);
+    hint.unwrap_or(0).min(max)
+}
+
+/// A type that can be built from a `HeaderVec`, used as the target of the deserialize functions
+/// in this module.
+pub trait FromHeaderVec: Sized {
+    type Head;
+    type Item;
+    fn from_header_vec(vec: HeaderVec<Self::Head, Self::Item>) -> Self;
+}
+
+impl<H, T> FromHeaderVec for HeaderVec<H, T> {
+    type Head = H;
+    type Item = T;
+    fn from_header_vec(vec: HeaderVec<H, T>) -> Self {
+        vec
+    }
+}
+
+impl<H, T> FromHeaderVec for Box<HeaderSlice<H, T>> {
+    type Head = H;
+    type Item = T;
+    fn from_header_vec(vec: HeaderVec<H, T>) -> Self {
+        vec.into_box()
+    }
+}
+
+impl<H: Serialize, T: Serialize> Serialize for HeaderSlice<H, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut tuple = serializer.serialize_tuple(2)?;
+        tuple.serialize_element(&self.head)?;
+        tuple.serialize_element(&self.body)?;
+        tuple.end()
+    }
+}
+
+impl<
+        H: Serialize,
+        T: Serialize,
+        A: crate::allocator::Allocator,
+        P: crate::growth::GrowthPolicy,
+    > Serialize for HeaderVec<H, T, A, P>
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (**self).serialize(serializer)
+    }
+}
+
+impl<'de, H: Deserialize<'de>, T: Deserialize<'de>> Deserialize<'de> for HeaderVec<H, T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_tuple(2, TupleVisitor(PhantomData, ElementsSeed::new))
+    }
+}
+
+impl<'de, H: Deserialize<'de>, T: Deserialize<'de>> Deserialize<'de> for Box<HeaderSlice<H, T>> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        HeaderVec::deserialize(deserializer).map(HeaderVec::into_box)
+    }
+}
+
+/// Deserializes a sequence of elements into a new `HeaderVec` with the given header.
+struct ElementsSeed<H, T> {
+    head: H,
+    _marker: PhantomData<T>,
+}
+
+impl<H, T> ElementsSeed<H, T> {
+    fn new(head: H) -> Self {
+        Self {
+            head,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'de, H, T: Deserialize<'de>> DeserializeSeed<'de> for ElementsSeed<H, T> {
+    type Value = HeaderVec<H, T>;
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, H, T: Deserialize<'de>> Visitor<'de> for ElementsSeed<H, T> {
+    type Value = HeaderVec<H, T>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a sequence")
+    }
+
+    fn visit_seq<S: SeqAccess<'de>>(self, mut seq: S) -> Result<Self::Value, S::Error> {
+        let cap = cautious_capacity::<T>(seq.size_hint());
+        let mut vec = HeaderVec::with_capacity(self.head, cap);
+        while let Some(val) = seq.next_element()? {
+            vec.push(val);
+        }
+        Ok(vec)
+    }
+}
+
+/// Deserializes a byte string into a new `HeaderVec` with the given header.
+struct BytesSeed<H> {
+    head: H,
+}
+
+impl<'de, H> DeserializeSeed<'de> for BytesSeed<H> {
+    type Value = HeaderVec<H, u8>;
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_bytes(self)
+    }
+}
+
+impl<'de, H> Visitor<'de> for BytesSeed<H> {
+    type Value = HeaderVec<H, u8>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a byte string")
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(HeaderVec::copy_from_slice(self.head, v))
+    }
+
+    fn visit_seq<S: SeqAccess<'de>>(self, seq: S) -> Result<Self::Value, S::Error> {
+        ElementsSeed::new(self.head).visit_seq(seq)
+    }
+}
+
+/// Visits a `(head, body)` tuple, using `F` to build the seed that deserializes the body.
+struct TupleVisitor<H, F>(PhantomData<H>, F);
+
+impl<'de, H, F, B> Visitor<'de> for TupleVisitor<H, F>
+where
+    H: Deserialize<'de>,
+    F: FnOnce(H) -> B,
+    B: DeserializeSeed<'de>,
+{
+    type Value = B::Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a tuple of a header and a sequence")
+    }
+
+    fn visit_seq<S: SeqAccess<'de>>(self, mut seq: S) -> Result<Self::Value, S::Error> {
+        let head = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        seq.next_element_seed((self.1)(head))?
+            .ok_or_else(|| de::Error::invalid_length(1, &"a tuple of size 2"))
+    }
+}
+
+/// Serialize and deserialize a `HeaderSlice` as a struct `{ head, body }`.
+///
+/// Use with `#[serde(with = "header_slice::serde::as_struct")]` on a `HeaderVec` or
+/// `Box<HeaderSlice>` field. When deserializing from a map, `head` should come before `body` so
+/// the elements can be streamed into the vector; otherwise they are buffered first.
+pub mod as_struct {
+    use super::*;
+
+    const FIELDS: &[&str] = &["head", "body"];
+
+    enum Field {
+        Head,
+        Body,
+    }
+
+    impl<'de> Deserialize<'de> for Field {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            struct FieldVisitor;
+
+            impl<'de> Visitor<'de> for FieldVisitor {
+                type Value = Field;
+
+                fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    f.write_str("`head` or `body`")
+                }
+
+                fn visit_u64<E: de::Error>(self, v: u64) -> Result<Field, E> {
+                    match v {
+                        0 => Ok(Field::Head),
+                        1 => Ok(Field::Body),
+                        _ => Err(de::Error::invalid_value(de::Unexpected::Unsigned(v), &self)),
+                    }
+                }
+
+                fn visit_str<E: de::Error>(self, v: &str) -> Result<Field, E> {
+                    match v {
+                        "head" => Ok(Field::Head),
+                        "body" => Ok(Field::Body),
+                        _ => Err(de::Error::unknown_field(v, FIELDS)),
+                    }
+                }
+            }
+
+            deserializer.deserialize_identifier(FieldVisitor)
+        }
+    }
+
+    struct StructVisitor<H, T>(PhantomData<(H, T)>);
+
+    impl<'de, H: Deserialize<'de>, T: Deserialize<'de>> Visitor<'de> for StructVisitor<H, T> {
+        type Value = HeaderVec<H, T>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a struct with a head and a body")
+        }
+
+        fn visit_seq<S: SeqAccess<'de>>(self, seq: S) -> Result<Self::Value, S::Error> {
+            TupleVisitor(PhantomData, ElementsSeed::new).visit_seq(seq)
+        }
+
+        fn visit_map<M: MapAccess<'de>>(self, mut map: M) -> Result<Self::Value, M::Error> {
+            let mut head = None;
+            let mut buffered = None;
+            while let Some(key) = map.next_key()? {
+                match key {
+                    Field::Head if head.is_some() => {
+                        return Err(de::Error::duplicate_field("head"));
+                    }
+                    Field::Head => head = Some(map.next_value()?),
+                    Field::Body => match head.take() {
+                        Some(head) => {
+                            let vec = map.next_value_seed(ElementsSeed::new(head))?;
+                            // reject any fields after the body
+                            if let Some(key) = map.next_key::<Field>()? {
+                                let name = match key {
+                                    Field::Head => "head",
+                                    Field::Body => "body",
+                                };
+                                return Err(de::Error::duplicate_field(name));
+                            }
+                            return Ok(vec);
+                        }
+                        None if buffered.is_some() => {
+                            return Err(de::Error::duplicate_field("body"));
+                        }
+                        None => buffered = Some(map.next_value::<Vec<T>>()?),
+                    },
+                }
+            }
+            let head = head.ok_or_else(|| de::Error::missing_field("head"))?;
+            let body = buffered.ok_or_else(|| de::Error::missing_field("body"))?;
+            Ok(HeaderVec::from_iter(head, body))
+        }
+    }
+
+    /// Serializes a `HeaderSlice` as a struct `{ head, body }`.
+    pub fn serialize<V, H, T, S>(value: &V, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        V: ?Sized + Deref<Target = HeaderSlice<H, T>>,
+        H: Serialize,
+        T: Serialize,
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("HeaderSlice", 2)?;
+        state.serialize_field("head", &value.head)?;
+        state.serialize_field("body", &value.body)?;
+        state.end()
+    }
+
+    /// Deserializes a `HeaderVec` or `Box<HeaderSlice>` from a struct `{ head, body }`.
+    pub fn deserialize<'de, V, D>(deserializer: D) -> Result<V, D::Error>
+    where
+        V: FromHeaderVec,
+        V::Head: Deserialize<'de>,
+        V::Item: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        deserializer
+            .deserialize_struct("HeaderSlice", FIELDS, StructVisitor(PhantomData))
+            .map(V::from_header_vec)
+    }
+}
+
+/// Serialize and deserialize a `HeaderSlice` with a `u8` body as a tuple `(head, bytes)`, where
+/// the body is a byte string rather than a sequence.
+///
+/// Use with `#[serde(with = "header_slice::serde::bytes")]` on a `HeaderVec` or
+/// `Box<HeaderSlice>` field.
+pub mod bytes {
+    use super::*;
+
+    struct Bytes<'a>(&'a [u8]);
+
+    impl Serialize for Bytes<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_bytes(self.0)
+        }
+    }
+
+    /// Serializes a `HeaderSlice` as a tuple `(head, bytes)`.
+    pub fn serialize<V, H, S>(value: &V, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        V: ?Sized + Deref<Target = HeaderSlice<H, u8>>,
+        H: Serialize,
+        S: Serializer,
+    {
+        let mut tuple = serializer.serialize_tuple(2)?;
+        tuple.serialize_element(&value.head)?;
+        tuple.serialize_element(&Bytes(&value.body))?;
+        tuple.end()
+    }
+
+    /// Deserializes a `HeaderVec` or `Box<HeaderSlice>` from a tuple `(head, bytes)`.
+    /// The body may also be a sequence of bytes.
+    pub fn deserialize<'de, V, D>(deserializer: D) -> Result<V, D::Error>
+    where
+        V: FromHeaderVec<Item = u8>,
+        V::Head: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        let visitor = TupleVisitor(PhantomData, |head| BytesSeed { head });
+        deserializer
+            .deserialize_tuple(2, visitor)
+            .map(V::from_header_vec)
+    }
+}
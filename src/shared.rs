@@ -0,0 +1,76 @@
+//! Conversions between header slices and the standard library's `Rc` and `Arc`.
+
+use crate::allocator::{self, Global};
+use crate::pair::Pair;
+use crate::{HeaderSlice, HeaderVec};
+use alloc::rc::Rc;
+use alloc::sync::Arc;
+use core::mem::{ManuallyDrop, MaybeUninit};
+use core::ptr;
+
+/// Moves the contents of `src` into a new `HeaderVec` with no excess capacity.
+/// The caller must make sure the contents of `src` are not used or dropped afterwards.
+unsafe fn move_into_vec<H, T>(src: *const HeaderSlice<H, T>) -> HeaderVec<H, T> {
+    let len = (*src).len();
+    let layout = HeaderSlice::<H, T>::layout_for_len(len);
+    let dst = allocator::allocate(&Global, layout);
+    ptr::copy_nonoverlapping(src as *const u8, dst.as_ptr(), layout.size());
+    HeaderVec::from_raw_parts(dst.cast::<Pair<H, MaybeUninit<T>>>(), len, len)
+}
+
+/// Casts a pointer to a `HeaderSlice` whose contents won't be dropped.
+fn forget_contents<H, T>(
+    ptr: *const HeaderSlice<H, T>,
+) -> *const HeaderSlice<ManuallyDrop<H>, ManuallyDrop<T>> {
+    // `ManuallyDrop` is `repr(transparent)`, so the cast preserves the layout
+    ptr as *const HeaderSlice<ManuallyDrop<H>, ManuallyDrop<T>>
+}
+
+macro_rules! shared_impls {
+    ($Rc:ident, $new_rc:ident, $try_from_rc:ident) => {
+        /// The reference counts are stored in front of the contents, so the conversion can't
+        /// reuse the vector's allocation: the vector is shrunk to fit with `into_box`, which may
+        /// move its contents, and they are then copied into a new allocation.
+        impl<H, T> From<HeaderVec<H, T>> for $Rc<HeaderSlice<H, T>> {
+            fn from(src: HeaderVec<H, T>) -> Self {
+                $Rc::from(src.into_box())
+            }
+        }
+
+        impl<H, T> HeaderSlice<H, T> {
+            /// Creates a reference-counted `HeaderSlice` from a header and the values of an
+            /// exact-size iterator.
+            /// The values are collected into a boxed `HeaderSlice` first, then copied into the
+            /// new allocation.
+            pub fn $new_rc<I>(head: H, iter: I) -> $Rc<Self>
+            where
+                I: IntoIterator<Item = T>,
+                I::IntoIter: ExactSizeIterator,
+            {
+                $Rc::from(Self::new_box(head, iter))
+            }
+        }
+
+        impl<H, T> HeaderVec<H, T> {
+            /// Moves the contents of a reference-counted `HeaderSlice` into a new `HeaderVec` if
+            /// there are no other strong or weak references to it. Otherwise, returns the pointer
+            /// unchanged.
+            pub fn $try_from_rc(
+                mut src: $Rc<HeaderSlice<H, T>>,
+            ) -> Result<Self, $Rc<HeaderSlice<H, T>>> {
+                if $Rc::get_mut(&mut src).is_none() {
+                    return Err(src);
+                }
+                let ptr = $Rc::into_raw(src);
+                unsafe {
+                    let vec = move_into_vec(ptr);
+                    drop($Rc::from_raw(forget_contents(ptr)));
+                    Ok(vec)
+                }
+            }
+        }
+    };
+}
+
+shared_impls!(Rc, new_rc, try_from_rc);
+shared_impls!(Arc, new_arc, try_from_arc);
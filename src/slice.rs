@@ -1,9 +1,26 @@
+#[cfg(feature = "alloc")]
+use crate::allocator::{self, Global};
+#[cfg(feature = "alloc")]
+use crate::pair::{pair_as_slice_ptr, Pair};
+use crate::view::{HeaderSliceMut, HeaderSliceRef};
+#[cfg(feature = "alloc")]
 use crate::HeaderVec;
-use alloc::alloc::Layout;
-use alloc::borrow::{Borrow, ToOwned};
+#[cfg(feature = "alloc")]
+use alloc::borrow::ToOwned;
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+use core::alloc::{Layout, LayoutError};
+use core::borrow::Borrow;
 use core::cmp::Ordering;
 use core::fmt::{self, Debug};
+#[cfg(feature = "alloc")]
+use core::iter;
+#[cfg(feature = "alloc")]
+use core::mem;
 use core::ptr;
+#[cfg(feature = "alloc")]
+use core::ptr::NonNull;
+use core::slice;
 
 #[repr(C)]
 #[derive(Hash)]
@@ -30,23 +47,178 @@ impl<H, T> HeaderSlice<H, T> {
         unsafe { &mut *ptr }
     }
 
+    /// Returns a mutable reference to this slice with its body resized to `len`.
+    ///
+    /// # Safety
+    /// The first `len` elements of the body must be within the allocation and initialized.
     pub unsafe fn resized_unchecked(&mut self, len: usize) -> &mut Self {
         let ptr = Self::resize_ptr(self as *mut _, len);
         &mut *ptr
     }
 
+    /// Borrows the header and body as a `HeaderSliceRef`, which can be narrowed to a sub-range of
+    /// the body.
+    pub fn as_view(&self) -> HeaderSliceRef<'_, H, T> {
+        HeaderSliceRef::new(&self.head, &self.body)
+    }
+
+    /// Mutably borrows the header and body as a `HeaderSliceMut`, which can be narrowed to a
+    /// sub-range of the body.
+    pub fn as_view_mut(&mut self) -> HeaderSliceMut<'_, H, T> {
+        HeaderSliceMut::new(&mut self.head, &mut self.body)
+    }
+
     pub fn len(&self) -> usize {
         self.body.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.body.is_empty()
+    }
+
+    /// Creates a boxed `HeaderSlice` from a header and the values of an exact-size iterator,
+    /// writing them directly into a single allocation of `layout_for_len(iter.len())`.
+    /// Values beyond the reported length are ignored.
+    /// Panics if the iterator yields fewer values than its reported length.
+    #[cfg(feature = "alloc")]
+    pub fn new_box<I>(head: H, iter: I) -> Box<Self>
+    where
+        I: IntoIterator<Item = T>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        /// Drops the header and the values written so far, then frees the allocation, if the
+        /// iterator panics or runs short.
+        struct DropGuard<H, T> {
+            ptr: NonNull<Pair<H, T>>,
+            len: usize,
+            layout: Layout,
+        }
+
+        impl<H, T> Drop for DropGuard<H, T> {
+            fn drop(&mut self) {
+                unsafe {
+                    ptr::drop_in_place(pair_as_slice_ptr(self.ptr, self.len).as_ptr());
+                    allocator::deallocate(&Global, self.ptr.cast(), self.layout);
+                }
+            }
+        }
+
+        let mut iter = iter.into_iter();
+        let len = iter.len();
+        let layout = Self::layout_for_len(len);
+        let ptr = allocator::allocate(&Global, layout).cast::<Pair<H, T>>();
+        unsafe { ptr::write(ptr::addr_of_mut!((*ptr.as_ptr()).0), head) };
+        let mut guard = DropGuard {
+            ptr,
+            len: 0,
+            layout,
+        };
+        let body_ptr = unsafe { ptr::addr_of_mut!((*ptr.as_ptr()).1) };
+        while guard.len < len {
+            let val = iter
+                .next()
+                .expect("iterator yielded fewer values than its reported length");
+            unsafe { ptr::write(body_ptr.add(guard.len), val) };
+            guard.len += 1;
+        }
+        mem::forget(guard);
+        unsafe { Box::from_raw(pair_as_slice_ptr(ptr, len).as_ptr()) }
+    }
+
+    /// Creates a boxed `HeaderSlice` from a header and a clone of each element of `src`,
+    /// allocating exactly `layout_for_len(src.len())`.
+    #[cfg(feature = "alloc")]
+    pub fn from_slice_box(head: H, src: &[T]) -> Box<Self>
+    where
+        T: Clone,
+    {
+        Self::new_box(head, src.iter().cloned())
+    }
+
+    /// Creates a boxed `HeaderSlice` of length `len`, where each element is the result of
+    /// calling `f` with its index. Allocates exactly `layout_for_len(len)`.
+    #[cfg(feature = "alloc")]
+    pub fn new_box_with(head: H, len: usize, f: impl FnMut(usize) -> T) -> Box<Self> {
+        Self::new_box(head, (0..len).map(f))
+    }
+
     /// Returns the memory layout for an instance with the given length
+    /// Panics if the layout would exceed `isize::MAX` bytes.
     pub fn layout_for_len(len: usize) -> Layout {
+        Self::try_layout_for_len(len).expect("capacity overflow")
+    }
+
+    /// Returns the memory layout for an instance with the given length, or an error if the layout
+    /// would exceed `isize::MAX` bytes.
+    pub fn try_layout_for_len(len: usize) -> Result<Layout, LayoutError> {
         let head_layout = Layout::new::<H>();
-        let buf_layout = Layout::array::<T>(len).unwrap();
-        head_layout.extend(buf_layout).unwrap().0.pad_to_align()
+        let buf_layout = Layout::array::<T>(len)?;
+        Ok(head_layout.extend(buf_layout)?.0.pad_to_align())
+    }
+
+    /// Returns the memory layout for an instance with the given length placed after a value of
+    /// type `P`, along with the offset of the instance within that layout.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn prefixed_layout_for_len<P>(len: usize) -> (Layout, usize) {
+        let (layout, offset) = Layout::new::<P>()
+            .extend(Self::layout_for_len(len))
+            .unwrap();
+        (layout.pad_to_align(), offset)
     }
 }
 
+impl<T> HeaderSlice<(), T> {
+    /// Views a slice as a `HeaderSlice` with no header.
+    pub fn from_slice(src: &[T]) -> &Self {
+        // a `HeaderSlice<(), T>` has the same layout as `[T]`
+        unsafe { &*(src as *const [T] as *const Self) }
+    }
+
+    /// Views a mutable slice as a `HeaderSlice` with no header.
+    pub fn from_slice_mut(src: &mut [T]) -> &mut Self {
+        unsafe { &mut *(src as *mut [T] as *mut Self) }
+    }
+
+    /// Converts a boxed slice into a boxed `HeaderSlice` with no header, reusing its allocation.
+    #[cfg(feature = "alloc")]
+    pub fn from_boxed_slice(src: Box<[T]>) -> Box<Self> {
+        unsafe { Box::from_raw(Box::into_raw(src) as *mut Self) }
+    }
+
+    /// Converts a boxed `HeaderSlice` with no header into a boxed slice, reusing its allocation.
+    #[cfg(feature = "alloc")]
+    pub fn into_boxed_slice(self: Box<Self>) -> Box<[T]> {
+        unsafe { Box::from_raw(Box::into_raw(self) as *mut [T]) }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> From<Box<[T]>> for Box<HeaderSlice<(), T>> {
+    fn from(src: Box<[T]>) -> Self {
+        HeaderSlice::from_boxed_slice(src)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> From<Box<HeaderSlice<(), T>>> for Box<[T]> {
+    fn from(src: Box<HeaderSlice<(), T>>) -> Self {
+        src.into_boxed_slice()
+    }
+}
+
+impl<'a, T> From<&'a [T]> for &'a HeaderSlice<(), T> {
+    fn from(src: &'a [T]) -> Self {
+        HeaderSlice::from_slice(src)
+    }
+}
+
+impl<'a, T> From<&'a mut [T]> for &'a mut HeaderSlice<(), T> {
+    fn from(src: &'a mut [T]) -> Self {
+        HeaderSlice::from_slice_mut(src)
+    }
+}
+
+#[cfg(feature = "alloc")]
 impl<H: Clone, T: Clone> ToOwned for HeaderSlice<H, T> {
     type Owned = HeaderVec<H, T>;
     fn to_owned(&self) -> Self::Owned {
@@ -54,23 +226,46 @@ impl<H: Clone, T: Clone> ToOwned for HeaderSlice<H, T> {
     }
 }
 
+#[cfg(feature = "alloc")]
+impl<H: Clone, T: Clone> Clone for Box<HeaderSlice<H, T>> {
+    fn clone(&self) -> Self {
+        HeaderSlice::from_slice_box(self.head.clone(), &self.body)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<H: Default, T> Default for Box<HeaderSlice<H, T>> {
+    fn default() -> Self {
+        HeaderSlice::new_box(H::default(), iter::empty())
+    }
+}
+
 impl<H: Debug, T: Debug> Debug for HeaderSlice<H, T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        f.write_str("[")?;
-        self.head.fmt(f)?;
-        if self.body.len() == 0 {
-            f.write_str(";]")?;
-        } else {
-            f.write_str("; ")?;
-            self.body[0].fmt(f)?;
-            for item in &self.body[1..] {
-                f.write_str(", ")?;
-                item.fmt(f)?;
-            }
-            f.write_str("]")?;
+        fmt_parts(&self.head, &self.body, f)
+    }
+}
+
+/// Formats a header and body like the `header_vec!` macro: `[head; a, b, c]`.
+pub(crate) fn fmt_parts<H: Debug, T: Debug>(
+    head: &H,
+    body: &[T],
+    f: &mut fmt::Formatter,
+) -> fmt::Result {
+    f.write_str("[")?;
+    head.fmt(f)?;
+    if body.is_empty() {
+        f.write_str(";]")?;
+    } else {
+        f.write_str("; ")?;
+        body[0].fmt(f)?;
+        for item in &body[1..] {
+            f.write_str(", ")?;
+            item.fmt(f)?;
         }
-        Ok(())
+        f.write_str("]")?;
     }
+    Ok(())
 }
 
 impl<H, T, Rhs: ?Sized> PartialEq<Rhs> for HeaderSlice<H, T>
@@ -110,3 +305,19 @@ impl<H: Ord, T: Ord> Ord for HeaderSlice<H, T> {
         }
     }
 }
+
+impl<'a, H, T> IntoIterator for &'a HeaderSlice<H, T> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.body.iter()
+    }
+}
+
+impl<'a, H, T> IntoIterator for &'a mut HeaderSlice<H, T> {
+    type Item = &'a mut T;
+    type IntoIter = slice::IterMut<'a, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.body.iter_mut()
+    }
+}
@@ -0,0 +1,391 @@
+use crate::inline::Inline;
+use crate::pair::{pair_as_slice_ptr, Pair};
+use crate::slice::HeaderSlice;
+use crate::vec::HeaderVec;
+use alloc::borrow::{Borrow, BorrowMut};
+use alloc::boxed::Box;
+use core::cmp::Ordering;
+use core::fmt::{self, Debug};
+use core::hash::{self, Hash};
+use core::iter;
+use core::mem::{self, MaybeUninit};
+use core::ops::{Add, AddAssign};
+use core::ops::{Deref, DerefMut};
+use core::ptr;
+
+enum Repr<H, T, const N: usize> {
+    Inline { data: Inline<H, T, N>, len: usize },
+    Heap(HeaderVec<H, T>),
+}
+
+/// A vector with a header, like `HeaderVec`, that stores its header and up to `N` elements
+/// inline. It only allocates once it grows past `N` elements.
+pub struct SmallHeaderVec<H, T, const N: usize> {
+    repr: Repr<H, T, N>,
+}
+
+impl<H, T, const N: usize> SmallHeaderVec<H, T, N> {
+    /// Creates an empty vector with the given header, without allocating.
+    pub fn new(head: H) -> Self {
+        Self {
+            repr: Repr::Inline {
+                data: Inline::new(head),
+                len: 0,
+            },
+        }
+    }
+
+    /// Creates an empty vector with the given header that can hold at least `cap` elements
+    /// without allocating again. Only allocates if `cap > N`.
+    pub fn with_capacity(head: H, cap: usize) -> Self {
+        if cap <= N {
+            Self::new(head)
+        } else {
+            Self {
+                repr: Repr::Heap(HeaderVec::with_capacity(head, cap)),
+            }
+        }
+    }
+
+    /// Creates a vector from the given header and iterator.
+    pub fn from_iter<I: IntoIterator<Item = T>>(head: H, iter: I) -> Self {
+        let iter = iter.into_iter();
+        let (lower, _) = iter.size_hint();
+        let mut this = Self::with_capacity(head, lower);
+        this.extend(iter);
+        this
+    }
+
+    /// Creates a vector from a `HeaderVec`, keeping its allocation.
+    pub fn from_header_vec(src: HeaderVec<H, T>) -> Self {
+        Self {
+            repr: Repr::Heap(src),
+        }
+    }
+
+    /// Converts the vector into a `HeaderVec`, allocating if it hasn't spilled to the heap.
+    pub fn into_header_vec(self) -> HeaderVec<H, T> {
+        let mut this = mem::ManuallyDrop::new(self);
+        if !this.spilled() {
+            let len = this.len();
+            unsafe { this.spill(len) };
+        }
+        match unsafe { ptr::read(&this.repr) } {
+            Repr::Heap(vec) => vec,
+            Repr::Inline { .. } => unreachable!(),
+        }
+    }
+
+    /// Converts the vector into a boxed `HeaderSlice`.
+    pub fn into_box(self) -> Box<HeaderSlice<H, T>> {
+        self.into_header_vec().into_box()
+    }
+
+    /// Returns `true` if the elements have been moved to a heap allocation.
+    pub fn spilled(&self) -> bool {
+        matches!(self.repr, Repr::Heap(_))
+    }
+
+    /// The total reserved capacity of the vector. This is `N` until it spills to the heap.
+    pub fn capacity(&self) -> usize {
+        match &self.repr {
+            Repr::Inline { .. } => N,
+            Repr::Heap(vec) => vec.capacity(),
+        }
+    }
+
+    /// Moves the header and elements to a heap allocation with capacity `cap`.
+    /// unsafe because `cap` must be at least the current length and the vector must be inline.
+    unsafe fn spill(&mut self, cap: usize) {
+        let len = self.len();
+        // allocate before moving anything out, in case the allocation panics
+        let heap = HeaderVec::<MaybeUninit<H>, T>::with_capacity(MaybeUninit::uninit(), cap);
+        let (heap_ptr, _, heap_cap) = heap.into_raw_parts();
+        let dst = pair_as_slice_ptr(heap_ptr, heap_cap).as_ptr();
+        let src = self.inline_ptr();
+        ptr::write((*dst).head.as_mut_ptr(), ptr::read(&(*src).head));
+        ptr::copy_nonoverlapping(
+            (*src).body.as_ptr(),
+            (*dst).body.as_mut_ptr() as *mut T,
+            len,
+        );
+        let vec =
+            HeaderVec::from_raw_parts(heap_ptr.cast::<Pair<H, MaybeUninit<T>>>(), len, heap_cap);
+        // the inline header and elements have been moved out, so don't drop them
+        ptr::write(&mut self.repr, Repr::Heap(vec));
+    }
+
+    /// Returns a pointer to the inline data as a `HeaderSlice` with length `N`.
+    fn inline_ptr(&mut self) -> *mut HeaderSlice<H, T> {
+        match &mut self.repr {
+            Repr::Inline { data, .. } => Inline::slice_ptr(data, N),
+            Repr::Heap(_) => unreachable!(),
+        }
+    }
+
+    /// Makes sure there is room for at least `additional` more elements, spilling to the heap
+    /// if necessary.
+    pub fn reserve(&mut self, additional: usize) {
+        let new_len = self
+            .len()
+            .checked_add(additional)
+            .expect("capacity overflow");
+        match &mut self.repr {
+            Repr::Heap(vec) => vec.reserve(additional),
+            Repr::Inline { .. } if new_len > N => unsafe { self.spill(new_len) },
+            Repr::Inline { .. } => {}
+        }
+    }
+
+    /// Push a value to the end of the vector.
+    pub fn push(&mut self, val: T) {
+        if let Repr::Inline { len, .. } = self.repr {
+            if len == N {
+                let target_len = len.checked_add(1).expect("capacity overflow");
+                unsafe { self.spill(target_len.saturating_mul(2)) };
+            }
+        }
+        match &mut self.repr {
+            Repr::Inline { data, len } => {
+                data.body[*len] = MaybeUninit::new(val);
+                *len += 1;
+            }
+            Repr::Heap(vec) => vec.push(val),
+        }
+    }
+
+    /// Removes the last value from the vector and returns it, or `None` if it is empty.
+    pub fn pop(&mut self) -> Option<T> {
+        match &mut self.repr {
+            Repr::Inline { len: 0, .. } => None,
+            Repr::Inline { data, len } => {
+                *len -= 1;
+                Some(unsafe { ptr::read(data.body[*len].as_ptr()) })
+            }
+            Repr::Heap(vec) => vec.pop(),
+        }
+    }
+
+    /// Inserts a value at `index`, shifting all elements after it to the right.
+    /// Panics if `index > len`.
+    pub fn insert(&mut self, index: usize, val: T) {
+        let len = self.len();
+        assert!(
+            index <= len,
+            "insertion index {} is out of bounds for length {}",
+            index,
+            len
+        );
+        match &mut self.repr {
+            Repr::Heap(vec) => vec.insert(index, val),
+            Repr::Inline { .. } => {
+                self.push(val);
+                self.body[index..].rotate_right(1);
+            }
+        }
+    }
+
+    /// Removes a value at the given index, if it exists.
+    /// All entries after `index` will be shifted to the left.
+    pub fn remove(&mut self, index: usize) -> Option<T> {
+        match &mut self.repr {
+            Repr::Heap(vec) => vec.remove(index),
+            Repr::Inline { .. } => {
+                if index >= self.len() {
+                    return None;
+                }
+                self.body[index..].rotate_left(1);
+                self.pop()
+            }
+        }
+    }
+
+    /// Drops all elements after `new_len`.
+    /// Panics if `new_len > len`.
+    pub fn truncate(&mut self, new_len: usize) {
+        match &mut self.repr {
+            Repr::Heap(vec) => vec.truncate(new_len),
+            Repr::Inline { data, len } => {
+                assert!(new_len <= *len);
+                let old_len = mem::replace(len, new_len);
+                unsafe { data.drop_range(new_len, old_len) };
+            }
+        }
+    }
+
+    /// Drops all elements in the vector. Keeps the allocation if it has spilled to the heap.
+    pub fn clear(&mut self) {
+        match &mut self.repr {
+            Repr::Heap(vec) => vec.clear_in_place(),
+            Repr::Inline { .. } => self.truncate(0),
+        }
+    }
+
+    /// Moves the elements back inline if there are at most `N` of them. Otherwise, frees any
+    /// excess heap capacity.
+    pub fn shrink_to_fit(&mut self) {
+        let vec = match &mut self.repr {
+            Repr::Heap(vec) if vec.len() <= N => unsafe { ptr::read(vec) },
+            Repr::Heap(vec) => return vec.shrink_to_fit(),
+            Repr::Inline { .. } => return,
+        };
+        let len = vec.len();
+        let (ptr, _, cap) = vec.into_raw_parts();
+        unsafe {
+            let src = pair_as_slice_ptr(ptr, cap).as_ptr();
+            let mut data = Inline::new(ptr::read(&(*src).head));
+            ptr::copy_nonoverlapping((*src).body.as_ptr(), data.body.as_mut_ptr(), len);
+            // the header and elements have been moved out, so only free the allocation
+            HeaderVec::<MaybeUninit<H>, MaybeUninit<T>>::from_raw_parts(ptr.cast(), 0, cap)
+                .dealloc_without_dropping();
+            ptr::write(&mut self.repr, Repr::Inline { data, len });
+        }
+    }
+}
+
+impl<H, T, const N: usize> Deref for SmallHeaderVec<H, T, N> {
+    type Target = HeaderSlice<H, T>;
+    fn deref(&self) -> &Self::Target {
+        match &self.repr {
+            Repr::Inline { data, len } => unsafe { data.as_header_slice(*len) },
+            Repr::Heap(vec) => vec,
+        }
+    }
+}
+
+impl<H, T, const N: usize> DerefMut for SmallHeaderVec<H, T, N> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        match &mut self.repr {
+            Repr::Inline { data, len } => unsafe { data.as_header_slice_mut(*len) },
+            Repr::Heap(vec) => vec,
+        }
+    }
+}
+
+impl<H, T, const N: usize> AsRef<HeaderSlice<H, T>> for SmallHeaderVec<H, T, N> {
+    fn as_ref(&self) -> &HeaderSlice<H, T> {
+        self.deref()
+    }
+}
+
+impl<H, T, const N: usize> AsMut<HeaderSlice<H, T>> for SmallHeaderVec<H, T, N> {
+    fn as_mut(&mut self) -> &mut HeaderSlice<H, T> {
+        self.deref_mut()
+    }
+}
+
+impl<H, T, const N: usize> Borrow<HeaderSlice<H, T>> for SmallHeaderVec<H, T, N> {
+    fn borrow(&self) -> &HeaderSlice<H, T> {
+        self.deref()
+    }
+}
+
+impl<H, T, const N: usize> BorrowMut<HeaderSlice<H, T>> for SmallHeaderVec<H, T, N> {
+    fn borrow_mut(&mut self) -> &mut HeaderSlice<H, T> {
+        self.deref_mut()
+    }
+}
+
+impl<H, T, const N: usize> Drop for SmallHeaderVec<H, T, N> {
+    fn drop(&mut self) {
+        // the header and heap vector are dropped with the fields
+        if let Repr::Inline { data, len } = &mut self.repr {
+            unsafe { data.drop_range(0, *len) };
+        }
+    }
+}
+
+impl<H: Clone, T: Clone, const N: usize> Clone for SmallHeaderVec<H, T, N> {
+    fn clone(&self) -> Self {
+        Self::from_iter(self.head.clone(), self.body.iter().cloned())
+    }
+}
+
+impl<H, T, const N: usize> Extend<T> for SmallHeaderVec<H, T, N> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for x in iter {
+            self.push(x);
+        }
+    }
+}
+
+impl<H, T, I: IntoIterator<Item = T>, const N: usize> AddAssign<I> for SmallHeaderVec<H, T, N> {
+    fn add_assign(&mut self, rhs: I) {
+        self.extend(rhs);
+    }
+}
+
+impl<H, T, I: IntoIterator<Item = T>, const N: usize> Add<I> for SmallHeaderVec<H, T, N> {
+    type Output = Self;
+    fn add(mut self, rhs: I) -> Self {
+        self += rhs;
+        self
+    }
+}
+
+impl<H, T, Rhs: ?Sized, const N: usize> PartialEq<Rhs> for SmallHeaderVec<H, T, N>
+where
+    H: PartialEq,
+    T: PartialEq,
+    Rhs: Borrow<HeaderSlice<H, T>>,
+{
+    fn eq(&self, rhs: &Rhs) -> bool {
+        self.deref() == rhs.borrow()
+    }
+}
+
+impl<H: Eq, T: Eq, const N: usize> Eq for SmallHeaderVec<H, T, N> {}
+
+impl<H, T, Rhs: ?Sized, const N: usize> PartialOrd<Rhs> for SmallHeaderVec<H, T, N>
+where
+    H: PartialOrd,
+    T: PartialOrd,
+    Rhs: Borrow<HeaderSlice<H, T>>,
+{
+    fn partial_cmp(&self, rhs: &Rhs) -> Option<Ordering> {
+        self.deref().partial_cmp(rhs.borrow())
+    }
+}
+
+impl<H: Ord, T: Ord, const N: usize> Ord for SmallHeaderVec<H, T, N> {
+    fn cmp(&self, rhs: &Self) -> Ordering {
+        self.deref().cmp(rhs.deref())
+    }
+}
+
+impl<H: Hash, T: Hash, const N: usize> Hash for SmallHeaderVec<H, T, N> {
+    fn hash<S: hash::Hasher>(&self, state: &mut S) {
+        self.deref().hash(state)
+    }
+}
+
+impl<H: Debug, T: Debug, const N: usize> Debug for SmallHeaderVec<H, T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let hslice: &HeaderSlice<H, T> = self.deref();
+        hslice.fmt(f)
+    }
+}
+
+impl<H: Default, T, const N: usize> iter::FromIterator<T> for SmallHeaderVec<H, T, N> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::from_iter(H::default(), iter)
+    }
+}
+
+impl<H, T, const N: usize> From<HeaderVec<H, T>> for SmallHeaderVec<H, T, N> {
+    fn from(src: HeaderVec<H, T>) -> Self {
+        Self::from_header_vec(src)
+    }
+}
+
+impl<H, T, const N: usize> From<SmallHeaderVec<H, T, N>> for HeaderVec<H, T> {
+    fn from(src: SmallHeaderVec<H, T, N>) -> Self {
+        src.into_header_vec()
+    }
+}
+
+impl<H: Default, T, const N: usize> Default for SmallHeaderVec<H, T, N> {
+    fn default() -> Self {
+        Self::new(H::default())
+    }
+}
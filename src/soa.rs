@@ -0,0 +1,401 @@
+use crate::allocator::{self, Global};
+use crate::error::{handle_reserve, TryReserveError};
+use crate::growth::{DefaultGrowth, GrowthPolicy};
+use crate::vec::MIN_CAP;
+use alloc::alloc::{Layout, LayoutError};
+use core::fmt::{self, Debug};
+use core::marker::PhantomData;
+use core::mem::{self, ManuallyDrop};
+use core::ptr::{self, NonNull};
+use core::slice;
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// A tuple of column types stored by a `HeaderSoa`. Implemented for tuples of 1 to 6 types.
+pub trait Columns: Sized + sealed::Sealed {
+    /// The byte offset of each column from the start of the allocation.
+    #[doc(hidden)]
+    type Offsets: Copy + AsRef<[usize]>;
+    /// A tuple with a shared slice of each column.
+    type Slices<'a>
+    where
+        Self: 'a;
+    /// A tuple with a mutable slice of each column.
+    type SlicesMut<'a>
+    where
+        Self: 'a;
+
+    /// Returns the memory layout for columns with capacity `cap` placed after `prefix`, along
+    /// with the offset of each column.
+    #[doc(hidden)]
+    fn layout_for_cap(prefix: Layout, cap: usize) -> Result<(Layout, Self::Offsets), LayoutError>;
+
+    /// Returns the size of an element of each column.
+    #[doc(hidden)]
+    fn sizes() -> Self::Offsets;
+
+    #[doc(hidden)]
+    unsafe fn write(base: *mut u8, offsets: &Self::Offsets, idx: usize, row: Self);
+
+    #[doc(hidden)]
+    unsafe fn read(base: *mut u8, offsets: &Self::Offsets, idx: usize) -> Self;
+
+    #[doc(hidden)]
+    unsafe fn slices<'a>(base: *mut u8, offsets: &Self::Offsets, len: usize) -> Self::Slices<'a>;
+
+    #[doc(hidden)]
+    unsafe fn slices_mut<'a>(
+        base: *mut u8,
+        offsets: &Self::Offsets,
+        len: usize,
+    ) -> Self::SlicesMut<'a>;
+
+    #[doc(hidden)]
+    unsafe fn drop_range(base: *mut u8, offsets: &Self::Offsets, start: usize, end: usize);
+}
+
+macro_rules! columns_impl {
+    ($n:literal; $($T:ident $i:tt),+) => {
+        impl<$($T),+> sealed::Sealed for ($($T,)+) {}
+
+        impl<$($T),+> Columns for ($($T,)+) {
+            type Offsets = [usize; $n];
+            type Slices<'a> = ($(&'a [$T],)+) where Self: 'a;
+            type SlicesMut<'a> = ($(&'a mut [$T],)+) where Self: 'a;
+
+            fn layout_for_cap(
+                prefix: Layout,
+                cap: usize,
+            ) -> Result<(Layout, Self::Offsets), LayoutError> {
+                let mut layout = prefix;
+                let mut offsets = [0; $n];
+                $(
+                    let (extended, offset) = layout.extend(Layout::array::<$T>(cap)?)?;
+                    layout = extended;
+                    offsets[$i] = offset;
+                )+
+                Ok((layout.pad_to_align(), offsets))
+            }
+
+            fn sizes() -> Self::Offsets {
+                [$(mem::size_of::<$T>()),+]
+            }
+
+            unsafe fn write(base: *mut u8, offsets: &Self::Offsets, idx: usize, row: Self) {
+                $((base.add(offsets[$i]) as *mut $T).add(idx).write(row.$i);)+
+            }
+
+            unsafe fn read(base: *mut u8, offsets: &Self::Offsets, idx: usize) -> Self {
+                ($((base.add(offsets[$i]) as *mut $T).add(idx).read(),)+)
+            }
+
+            unsafe fn slices<'a>(
+                base: *mut u8,
+                offsets: &Self::Offsets,
+                len: usize,
+            ) -> Self::Slices<'a> {
+                ($(slice::from_raw_parts(base.add(offsets[$i]) as *const $T, len),)+)
+            }
+
+            unsafe fn slices_mut<'a>(
+                base: *mut u8,
+                offsets: &Self::Offsets,
+                len: usize,
+            ) -> Self::SlicesMut<'a> {
+                ($(slice::from_raw_parts_mut(base.add(offsets[$i]) as *mut $T, len),)+)
+            }
+
+            unsafe fn drop_range(
+                base: *mut u8,
+                offsets: &Self::Offsets,
+                start: usize,
+                end: usize,
+            ) {
+                $(
+                    let column = (base.add(offsets[$i]) as *mut $T).add(start);
+                    ptr::drop_in_place(ptr::slice_from_raw_parts_mut(column, end - start));
+                )+
+            }
+        }
+    };
+}
+
+columns_impl!(1; A 0);
+columns_impl!(2; A 0, B 1);
+columns_impl!(3; A 0, B 1, C 2);
+columns_impl!(4; A 0, B 1, C 2, D 3);
+columns_impl!(5; A 0, B 1, C 2, D 3, E 4);
+columns_impl!(6; A 0, B 1, C 2, D 3, E 4, F 5);
+
+/// A vector with a header and several parallel columns of equal length, stored as a
+/// structure of arrays in a single allocation.
+///
+/// `C` is a tuple of the column types, and each row is a value of that tuple type. The header is
+/// followed by each column in turn, using the same layout rules as `HeaderSlice`.
+pub struct HeaderSoa<H, C: Columns> {
+    ptr: NonNull<u8>,
+    len: usize,
+    cap: usize,
+    offsets: C::Offsets,
+    _marker: PhantomData<(H, C)>,
+}
+
+impl<H, C: Columns> HeaderSoa<H, C> {
+    /// Creates an empty vector with the given header.
+    pub fn new(head: H) -> Self {
+        Self::with_capacity(head, MIN_CAP)
+    }
+
+    /// Creates an empty vector with the given header and capacity for `cap` rows.
+    pub fn with_capacity(head: H, cap: usize) -> Self {
+        let (layout, offsets) = Self::layout_for_cap(cap);
+        let ptr = allocator::allocate(&Global, layout);
+        unsafe { ptr::write(ptr.as_ptr() as *mut H, head) };
+        Self {
+            ptr,
+            len: 0,
+            cap,
+            offsets,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Creates a vector from the given header and iterator of rows.
+    pub fn from_iter<I: IntoIterator<Item = C>>(head: H, iter: I) -> Self {
+        let iter = iter.into_iter();
+        let (lower, _) = iter.size_hint();
+        let mut this = Self::with_capacity(head, lower);
+        this.extend(iter);
+        this
+    }
+
+    /// Returns the memory layout for the given capacity and the offset of each column.
+    /// Panics if the layout would exceed `isize::MAX` bytes.
+    fn layout_for_cap(cap: usize) -> (Layout, C::Offsets) {
+        C::layout_for_cap(Layout::new::<H>(), cap).expect("capacity overflow")
+    }
+
+    /// Returns a reference to the header.
+    pub fn head(&self) -> &H {
+        unsafe { &*(self.ptr.as_ptr() as *const H) }
+    }
+
+    /// Returns a mutable reference to the header.
+    pub fn head_mut(&mut self) -> &mut H {
+        unsafe { &mut *(self.ptr.as_ptr() as *mut H) }
+    }
+
+    /// The number of rows in the vector.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the vector has no rows.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The number of rows the vector can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.cap
+    }
+
+    /// Returns a tuple with a slice of each column.
+    pub fn slices(&self) -> C::Slices<'_> {
+        unsafe { C::slices(self.ptr.as_ptr(), &self.offsets, self.len) }
+    }
+
+    /// Returns a tuple with a mutable slice of each column.
+    pub fn slices_mut(&mut self) -> C::SlicesMut<'_> {
+        unsafe { C::slices_mut(self.ptr.as_ptr(), &self.offsets, self.len) }
+    }
+
+    /// Copies `count` rows from `src` to `dst` in every column. The ranges may overlap.
+    unsafe fn copy_rows(&mut self, src: usize, dst: usize, count: usize) {
+        let base = self.ptr.as_ptr();
+        let sizes = C::sizes();
+        for (&offset, &size) in self.offsets.as_ref().iter().zip(sizes.as_ref()) {
+            let column = base.add(offset);
+            ptr::copy(column.add(src * size), column.add(dst * size), count * size);
+        }
+    }
+
+    /// Reallocate so that the vector has the exact requested capacity, moving each column to
+    /// its new offset.
+    /// unsafe because the new capacity may be less than self.len
+    unsafe fn realloc_exact(&mut self, cap: usize) {
+        if cap == self.cap {
+            return;
+        }
+        let (old_layout, _) = Self::layout_for_cap(self.cap);
+        let (new_layout, new_offsets) = Self::layout_for_cap(cap);
+        let sizes = C::sizes();
+        let columns = new_offsets
+            .as_ref()
+            .iter()
+            .zip(self.offsets.as_ref())
+            .zip(sizes.as_ref());
+        if cap > self.cap {
+            let ptr = allocator::reallocate(&Global, self.ptr, old_layout, new_layout);
+            // columns only move towards the end, so move the last one first
+            for ((&new, &old), &size) in columns.rev() {
+                let base = ptr.as_ptr();
+                ptr::copy(base.add(old), base.add(new), self.len * size);
+            }
+            self.ptr = ptr;
+        } else {
+            // columns only move towards the start, so move the first one first
+            for ((&new, &old), &size) in columns {
+                let base = self.ptr.as_ptr();
+                ptr::copy(base.add(old), base.add(new), self.len * size);
+            }
+            self.ptr = allocator::reallocate(&Global, self.ptr, old_layout, new_layout);
+        }
+        self.cap = cap;
+        self.offsets = new_offsets;
+    }
+
+    /// Reserve enough capacity to add at least `additional` rows without reallocating.
+    pub fn reserve(&mut self, additional: usize) {
+        let new_len = self.len.checked_add(additional);
+        let new_len = handle_reserve(new_len.ok_or(TryReserveError::CapacityOverflow));
+        if new_len > self.cap {
+            let target_cap = DefaultGrowth::grow(self.cap, new_len);
+            unsafe { self.realloc_exact(target_cap) };
+        }
+    }
+
+    /// Reallocates so there is no excess capacity (i.e. capacity == length).
+    pub fn shrink_to_fit(&mut self) {
+        unsafe { self.realloc_exact(self.len) }
+    }
+
+    /// Push a row to the end of the vector.
+    pub fn push(&mut self, row: C) {
+        self.reserve(1);
+        unsafe { C::write(self.ptr.as_ptr(), &self.offsets, self.len, row) };
+        self.len += 1;
+    }
+
+    /// Removes the last row from the vector and returns it, or `None` if it is empty.
+    pub fn pop(&mut self) -> Option<C> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        Some(unsafe { C::read(self.ptr.as_ptr(), &self.offsets, self.len) })
+    }
+
+    /// Inserts a row at `index`, shifting all rows after it down.
+    /// Panics if `index > len`.
+    pub fn insert(&mut self, index: usize, row: C) {
+        let len = self.len;
+        assert!(
+            index <= len,
+            "insertion index {} is out of bounds for length {}",
+            index,
+            len
+        );
+        self.reserve(1);
+        unsafe {
+            self.copy_rows(index, index + 1, len - index);
+            C::write(self.ptr.as_ptr(), &self.offsets, index, row);
+        }
+        self.len += 1;
+    }
+
+    /// Removes the row at the given index, if it exists.
+    /// All rows after `index` will be shifted up.
+    pub fn remove(&mut self, index: usize) -> Option<C> {
+        let len = self.len;
+        if index >= len {
+            return None;
+        }
+        unsafe {
+            let row = C::read(self.ptr.as_ptr(), &self.offsets, index);
+            self.copy_rows(index + 1, index, len - index - 1);
+            self.len -= 1;
+            Some(row)
+        }
+    }
+
+    /// Removes the row at the given index, if it exists, and replaces it with the last row.
+    pub fn swap_remove(&mut self, index: usize) -> Option<C> {
+        let len = self.len;
+        if index >= len {
+            return None;
+        }
+        unsafe {
+            let row = C::read(self.ptr.as_ptr(), &self.offsets, index);
+            self.copy_rows(len - 1, index, 1);
+            self.len -= 1;
+            Some(row)
+        }
+    }
+
+    /// Drops all rows after `new_len`.
+    /// Panics if `new_len > len`.
+    pub fn truncate(&mut self, new_len: usize) {
+        assert!(new_len <= self.len);
+        let old_len = mem::replace(&mut self.len, new_len);
+        unsafe { C::drop_range(self.ptr.as_ptr(), &self.offsets, new_len, old_len) };
+    }
+
+    /// Drops all rows in the vector, keeping the allocation.
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+}
+
+impl<H, C: Columns> Drop for HeaderSoa<H, C> {
+    fn drop(&mut self) {
+        let (layout, _) = Self::layout_for_cap(self.cap);
+        unsafe {
+            C::drop_range(self.ptr.as_ptr(), &self.offsets, 0, self.len);
+            ptr::drop_in_place(self.ptr.as_ptr() as *mut H);
+            allocator::deallocate(&Global, self.ptr, layout);
+        }
+    }
+}
+
+impl<H: Clone, C: Columns + Clone> Clone for HeaderSoa<H, C> {
+    fn clone(&self) -> Self {
+        let mut out = Self::with_capacity(self.head().clone(), self.len);
+        for i in 0..self.len {
+            // a bitwise copy of the row that is never dropped, so the original keeps ownership
+            let row = ManuallyDrop::new(unsafe { C::read(self.ptr.as_ptr(), &self.offsets, i) });
+            out.push(C::clone(&row));
+        }
+        out
+    }
+}
+
+impl<H, C: Columns> Extend<C> for HeaderSoa<H, C> {
+    fn extend<I: IntoIterator<Item = C>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for row in iter {
+            self.push(row);
+        }
+    }
+}
+
+impl<H: Debug, C: Columns> Debug for HeaderSoa<H, C>
+where
+    for<'a> C::Slices<'a>: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("HeaderSoa")
+            .field("head", self.head())
+            .field("columns", &self.slices())
+            .finish()
+    }
+}
+
+impl<H: Default, C: Columns> Default for HeaderSoa<H, C> {
+    fn default() -> Self {
+        Self::new(H::default())
+    }
+}
@@ -0,0 +1,469 @@
+use crate::slice::HeaderSlice;
+use crate::vec::HeaderVec;
+use alloc::borrow::{Borrow, BorrowMut, ToOwned};
+use alloc::boxed::Box;
+use core::cmp::Ordering;
+use core::fmt::{self, Debug};
+use core::hash::{self, Hash};
+use core::ops::{Deref, DerefMut};
+use core::str::{self, Utf8Error};
+
+/// A `HeaderSlice` whose body is valid UTF-8.
+#[repr(C)]
+#[derive(Hash)]
+pub struct HeaderStr<H> {
+    pub head: H,
+    pub body: str,
+}
+
+impl<H> HeaderStr<H> {
+    /// Converts a `HeaderSlice` of bytes to a `HeaderStr` if its body is valid UTF-8.
+    pub fn from_header_slice(src: &HeaderSlice<H, u8>) -> Result<&Self, Utf8Error> {
+        str::from_utf8(&src.body)?;
+        Ok(unsafe { Self::from_header_slice_unchecked(src) })
+    }
+
+    /// Converts a mutable `HeaderSlice` of bytes to a `HeaderStr` if its body is valid UTF-8.
+    pub fn from_header_slice_mut(src: &mut HeaderSlice<H, u8>) -> Result<&mut Self, Utf8Error> {
+        str::from_utf8(&src.body)?;
+        Ok(unsafe { Self::from_header_slice_unchecked_mut(src) })
+    }
+
+    /// Converts a `HeaderSlice` of bytes to a `HeaderStr` without checking its body.
+    ///
+    /// # Safety
+    /// The body must be valid UTF-8.
+    pub unsafe fn from_header_slice_unchecked(src: &HeaderSlice<H, u8>) -> &Self {
+        &*(src as *const HeaderSlice<H, u8> as *const Self)
+    }
+
+    /// Converts a mutable `HeaderSlice` of bytes to a `HeaderStr` without checking its body.
+    ///
+    /// # Safety
+    /// The body must be valid UTF-8.
+    pub unsafe fn from_header_slice_unchecked_mut(src: &mut HeaderSlice<H, u8>) -> &mut Self {
+        &mut *(src as *mut HeaderSlice<H, u8> as *mut Self)
+    }
+
+    /// Returns the header and the bytes of the body as a `HeaderSlice`.
+    pub fn as_header_slice(&self) -> &HeaderSlice<H, u8> {
+        unsafe { &*(self as *const Self as *const HeaderSlice<H, u8>) }
+    }
+
+    /// Returns the header and the bytes of the body as a mutable `HeaderSlice`.
+    ///
+    /// # Safety
+    /// The body must still be valid UTF-8 when the borrow ends.
+    pub unsafe fn as_header_slice_mut(&mut self) -> &mut HeaderSlice<H, u8> {
+        &mut *(self as *mut Self as *mut HeaderSlice<H, u8>)
+    }
+}
+
+impl<H> Deref for HeaderStr<H> {
+    type Target = str;
+    fn deref(&self) -> &str {
+        &self.body
+    }
+}
+
+impl<H> DerefMut for HeaderStr<H> {
+    fn deref_mut(&mut self) -> &mut str {
+        &mut self.body
+    }
+}
+
+impl<H: Clone> ToOwned for HeaderStr<H> {
+    type Owned = HeaderString<H>;
+    fn to_owned(&self) -> Self::Owned {
+        HeaderString::from_str(self.head.clone(), &self.body)
+    }
+}
+
+impl<H: Debug> Debug for HeaderStr<H> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("[")?;
+        self.head.fmt(f)?;
+        f.write_str("; ")?;
+        self.body.fmt(f)?;
+        f.write_str("]")
+    }
+}
+
+impl<H, Rhs: ?Sized> PartialEq<Rhs> for HeaderStr<H>
+where
+    H: PartialEq,
+    Rhs: Borrow<HeaderStr<H>>,
+{
+    fn eq(&self, rhs: &Rhs) -> bool {
+        let rhs = rhs.borrow();
+        self.head == rhs.head && self.body == rhs.body
+    }
+}
+
+impl<H: Eq> Eq for HeaderStr<H> {}
+
+impl<H, Rhs: ?Sized> PartialOrd<Rhs> for HeaderStr<H>
+where
+    H: PartialOrd,
+    Rhs: Borrow<HeaderStr<H>>,
+{
+    fn partial_cmp(&self, rhs: &Rhs) -> Option<Ordering> {
+        let rhs = rhs.borrow();
+        partial_ord_chain! {
+            self.head => rhs.head,
+            self.body => rhs.body,
+        }
+    }
+}
+
+impl<H: Ord> Ord for HeaderStr<H> {
+    fn cmp(&self, rhs: &Self) -> Ordering {
+        ord_chain! {
+            self.head => rhs.head,
+            self.body => rhs.body,
+        }
+    }
+}
+
+/// A string with a header allocated inline, built on `HeaderVec<H, u8>`.
+pub struct HeaderString<H> {
+    vec: HeaderVec<H, u8>,
+}
+
+impl<H> HeaderString<H> {
+    /// Creates an empty `HeaderString`.
+    pub fn new(head: H) -> Self {
+        Self {
+            vec: HeaderVec::new(head),
+        }
+    }
+
+    /// Creates an empty `HeaderString` with the specified capacity in bytes.
+    pub fn with_capacity(head: H, cap: usize) -> Self {
+        Self {
+            vec: HeaderVec::with_capacity(head, cap),
+        }
+    }
+
+    /// Copies the given string into a new `HeaderString`.
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(head: H, src: &str) -> Self {
+        Self {
+            vec: HeaderVec::copy_from_slice(head, src.as_bytes()),
+        }
+    }
+
+    /// Converts a vector of bytes to a `HeaderString` if its body is valid UTF-8.
+    pub fn from_utf8(vec: HeaderVec<H, u8>) -> Result<Self, FromUtf8Error<H>> {
+        match str::from_utf8(&vec.body) {
+            Ok(_) => Ok(Self { vec }),
+            Err(error) => Err(FromUtf8Error { vec, error }),
+        }
+    }
+
+    /// Converts a vector of bytes to a `HeaderString` without checking its body.
+    ///
+    /// # Safety
+    /// The body must be valid UTF-8.
+    pub unsafe fn from_utf8_unchecked(vec: HeaderVec<H, u8>) -> Self {
+        Self { vec }
+    }
+
+    /// Returns the underlying vector of bytes.
+    pub fn into_header_vec(self) -> HeaderVec<H, u8> {
+        self.vec
+    }
+
+    /// Returns a mutable reference to the underlying vector of bytes.
+    ///
+    /// # Safety
+    /// The body must still be valid UTF-8 when the borrow ends.
+    pub unsafe fn as_mut_header_vec(&mut self) -> &mut HeaderVec<H, u8> {
+        &mut self.vec
+    }
+
+    /// The total reserved capacity of the string in bytes.
+    pub fn capacity(&self) -> usize {
+        self.vec.capacity()
+    }
+
+    /// Reserve enough capacity to add at least `additional` bytes without realllocating.
+    pub fn reserve(&mut self, additional: usize) {
+        self.vec.reserve(additional)
+    }
+
+    /// Reserve enough capacity to add exactly `additional` bytes without realllocating.
+    pub fn reserve_exact(&mut self, additional: usize) {
+        self.vec.reserve_exact(additional)
+    }
+
+    /// Reallocates so there is no excess capacity.
+    pub fn shrink_to_fit(&mut self) {
+        self.vec.shrink_to_fit()
+    }
+
+    /// Appends a string to the end of the body.
+    pub fn push_str(&mut self, s: &str) {
+        self.vec.extend_from_slice(s.as_bytes())
+    }
+
+    /// Appends a character to the end of the body.
+    pub fn push(&mut self, ch: char) {
+        self.push_str(ch.encode_utf8(&mut [0; 4]))
+    }
+
+    /// Removes the last character of the body, if there is one.
+    pub fn pop(&mut self) -> Option<char> {
+        let ch = self.body.chars().next_back()?;
+        self.vec.truncate(self.vec.len() - ch.len_utf8());
+        Some(ch)
+    }
+
+    /// Inserts a string at the byte index `index`, shifting everything after it to the right.
+    /// Panics if `index` is not on a character boundary.
+    pub fn insert_str(&mut self, index: usize, s: &str) {
+        assert!(self.body.is_char_boundary(index));
+        self.vec.extend_from_slice(s.as_bytes());
+        self.vec.body[index..].rotate_right(s.len());
+    }
+
+    /// Inserts a character at the byte index `index`, shifting everything after it to the right.
+    /// Panics if `index` is not on a character boundary.
+    pub fn insert(&mut self, index: usize, ch: char) {
+        self.insert_str(index, ch.encode_utf8(&mut [0; 4]))
+    }
+
+    /// Removes the character starting at the byte index `index`, shifting everything after it
+    /// to the left.
+    /// Panics if `index` is not on a character boundary or is at the end of the body.
+    pub fn remove(&mut self, index: usize) -> char {
+        let ch = match self.body[index..].chars().next() {
+            Some(ch) => ch,
+            None => panic!("cannot remove a char from the end of a string"),
+        };
+        let len = self.vec.len();
+        self.vec.body[index..].rotate_left(ch.len_utf8());
+        self.vec.truncate(len - ch.len_utf8());
+        ch
+    }
+
+    /// Shortens the body to `new_len` bytes.
+    /// Panics if `new_len > self.len()` or `new_len` is not on a character boundary.
+    pub fn truncate(&mut self, new_len: usize) {
+        assert!(self.body.is_char_boundary(new_len));
+        self.vec.truncate(new_len)
+    }
+
+    /// Delete the body and reallocate so there is no excess capacity.
+    pub fn clear(&mut self) {
+        self.vec.clear()
+    }
+
+    /// Converts the string into a boxed `HeaderStr`.
+    pub fn into_box(self) -> Box<HeaderStr<H>> {
+        let ptr = Box::into_raw(self.vec.into_box());
+        unsafe { Box::from_raw(ptr as *mut HeaderStr<H>) }
+    }
+
+    /// Creates a string from a boxed `HeaderStr`.
+    pub fn from_box(src: Box<HeaderStr<H>>) -> Self {
+        let ptr = Box::into_raw(src) as *mut HeaderSlice<H, u8>;
+        let vec = HeaderVec::from_box(unsafe { Box::from_raw(ptr) });
+        Self { vec }
+    }
+}
+
+impl<H> Deref for HeaderString<H> {
+    type Target = HeaderStr<H>;
+    fn deref(&self) -> &Self::Target {
+        unsafe { HeaderStr::from_header_slice_unchecked(&self.vec) }
+    }
+}
+
+impl<H> DerefMut for HeaderString<H> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { HeaderStr::from_header_slice_unchecked_mut(&mut self.vec) }
+    }
+}
+
+impl<H> AsRef<HeaderStr<H>> for HeaderString<H> {
+    fn as_ref(&self) -> &HeaderStr<H> {
+        self.deref()
+    }
+}
+
+impl<H> AsMut<HeaderStr<H>> for HeaderString<H> {
+    fn as_mut(&mut self) -> &mut HeaderStr<H> {
+        self.deref_mut()
+    }
+}
+
+impl<H> Borrow<HeaderStr<H>> for HeaderString<H> {
+    fn borrow(&self) -> &HeaderStr<H> {
+        self.deref()
+    }
+}
+
+impl<H> BorrowMut<HeaderStr<H>> for HeaderString<H> {
+    fn borrow_mut(&mut self) -> &mut HeaderStr<H> {
+        self.deref_mut()
+    }
+}
+
+impl<H> fmt::Write for HeaderString<H> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s);
+        Ok(())
+    }
+
+    fn write_char(&mut self, ch: char) -> fmt::Result {
+        self.push(ch);
+        Ok(())
+    }
+}
+
+impl<H: Clone> Clone for HeaderString<H> {
+    fn clone(&self) -> Self {
+        Self {
+            vec: self.vec.clone(),
+        }
+    }
+}
+
+impl<'a, H> Extend<&'a str> for HeaderString<H> {
+    fn extend<I: IntoIterator<Item = &'a str>>(&mut self, iter: I) {
+        for s in iter {
+            self.push_str(s);
+        }
+    }
+}
+
+impl<H> Extend<char> for HeaderString<H> {
+    fn extend<I: IntoIterator<Item = char>>(&mut self, iter: I) {
+        for ch in iter {
+            self.push(ch);
+        }
+    }
+}
+
+impl<H, Rhs: ?Sized> PartialEq<Rhs> for HeaderString<H>
+where
+    H: PartialEq,
+    Rhs: Borrow<HeaderStr<H>>,
+{
+    fn eq(&self, rhs: &Rhs) -> bool {
+        self.deref() == rhs.borrow()
+    }
+}
+
+impl<H: Eq> Eq for HeaderString<H> {}
+
+impl<H, Rhs: ?Sized> PartialOrd<Rhs> for HeaderString<H>
+where
+    H: PartialOrd,
+    Rhs: Borrow<HeaderStr<H>>,
+{
+    fn partial_cmp(&self, rhs: &Rhs) -> Option<Ordering> {
+        self.deref().partial_cmp(rhs.borrow())
+    }
+}
+
+impl<H: Ord> Ord for HeaderString<H> {
+    fn cmp(&self, rhs: &Self) -> Ordering {
+        self.deref().cmp(rhs.deref())
+    }
+}
+
+impl<H: Hash> Hash for HeaderString<H> {
+    fn hash<S: hash::Hasher>(&self, state: &mut S) {
+        self.deref().hash(state)
+    }
+}
+
+impl<H: Debug> Debug for HeaderString<H> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let hstr: &HeaderStr<H> = self.deref();
+        hstr.fmt(f)
+    }
+}
+
+impl<H: Default> Default for HeaderString<H> {
+    fn default() -> Self {
+        Self::new(H::default())
+    }
+}
+
+impl<H> From<HeaderString<H>> for HeaderVec<H, u8> {
+    fn from(src: HeaderString<H>) -> Self {
+        src.into_header_vec()
+    }
+}
+
+impl<H> From<Box<HeaderStr<H>>> for HeaderString<H> {
+    fn from(src: Box<HeaderStr<H>>) -> Self {
+        Self::from_box(src)
+    }
+}
+
+impl<H> From<HeaderString<H>> for Box<HeaderStr<H>> {
+    fn from(src: HeaderString<H>) -> Self {
+        src.into_box()
+    }
+}
+
+/// The error returned when converting a vector of bytes that isn't valid UTF-8 to a
+/// `HeaderString`.
+pub struct FromUtf8Error<H> {
+    vec: HeaderVec<H, u8>,
+    error: Utf8Error,
+}
+
+impl<H> FromUtf8Error<H> {
+    /// Returns the vector that failed to convert.
+    pub fn into_header_vec(self) -> HeaderVec<H, u8> {
+        self.vec
+    }
+
+    /// Returns details about where the conversion failed.
+    pub fn utf8_error(&self) -> Utf8Error {
+        self.error
+    }
+}
+
+impl<H: Debug> Debug for FromUtf8Error<H> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FromUtf8Error")
+            .field("vec", &self.vec)
+            .field("error", &self.error)
+            .finish()
+    }
+}
+
+impl<H> fmt::Display for FromUtf8Error<H> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.error, f)
+    }
+}
+
+/// Creates a `HeaderString` with the given header and body.
+///
+/// ## Examples:
+/// - `header_string!["foo"; "bar"]`
+/// - `header_string![123; "{} + {}", 1, 2]`
+#[macro_export]
+macro_rules! header_string {
+    // Take a header only:
+    ($h:expr;) => {
+        $crate::string::HeaderString::new($h)
+    };
+    // Take a format string and arguments:
+    ($h:expr; $fmt:literal, $($arg:tt)*) => {{
+        let mut s = $crate::string::HeaderString::new($h);
+        core::fmt::Write::write_fmt(&mut s, core::format_args!($fmt, $($arg)*)).unwrap();
+        s
+    }};
+    // Take a string:
+    ($h:expr; $s:expr) => {
+        $crate::string::HeaderString::from_str($h, $s)
+    };
+}
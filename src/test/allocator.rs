@@ -0,0 +1,133 @@
+use crate::{header_vec, HeaderSlice, HeaderVec};
+use allocator_api2::alloc::{AllocError, Allocator, Global, Layout};
+use allocator_api2::boxed::Box;
+use core::cell::Cell;
+use core::ptr::NonNull;
+
+/// Wraps the global allocator, tracking the number of live allocations.
+#[derive(Clone, Copy)]
+struct Counting<'a>(&'a Cell<isize>);
+
+unsafe impl Allocator for Counting<'_> {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.0.set(self.0.get() + 1);
+        Global.allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        self.0.set(self.0.get() - 1);
+        Global.deallocate(ptr, layout)
+    }
+}
+
+#[test]
+fn new_in() {
+    let count = Cell::new(0);
+    let mut v = HeaderVec::new_in("foo", Counting(&count));
+    assert_eq!(count.get(), 1);
+    v.extend(0..100);
+    assert_eq!(count.get(), 1);
+    assert_eq!(v, HeaderVec::from_iter("foo", 0..100));
+    drop(v);
+    assert_eq!(count.get(), 0);
+}
+
+#[test]
+fn with_capacity_in() {
+    let count = Cell::new(0);
+    let v = HeaderVec::<_, i32, _>::with_capacity_in("foo", 10, Counting(&count));
+    assert_eq!(v.capacity(), 10);
+    let v2 = v.clone();
+    assert_eq!(count.get(), 2);
+    drop((v, v2));
+    assert_eq!(count.get(), 0);
+}
+
+#[test]
+fn into_values() {
+    let count = Cell::new(0);
+    let v = HeaderVec::from_iter_in("foo", 0..4, Counting(&count));
+    let (head, mut values) = v.into_header_values();
+    assert_eq!(head, "foo");
+    assert_eq!(values.next(), Some(0));
+    drop(values);
+    assert_eq!(count.get(), 0);
+}
+
+#[test]
+fn into_box_in() {
+    let count = Cell::new(0);
+    let v = HeaderVec::copy_from_slice_in("foo", &[1, 2, 3], Counting(&count));
+    let b: Box<HeaderSlice<_, _>, _> = v.into_box_in();
+    assert_eq!(*b, header_vec!["foo"; 1, 2, 3]);
+    let v = HeaderVec::from(b);
+    assert_eq!(v, header_vec!["foo"; 1, 2, 3]);
+    drop(v);
+    assert_eq!(count.get(), 0);
+}
+
+#[test]
+fn map_in() {
+    let count = Cell::new(0);
+    let v = HeaderVec::from_iter_in(1u8, 0..10u16, Counting(&count));
+    let v = v.map_head(|h| u64::from(h) + 1);
+    assert_eq!(v.head, 2);
+    let v = v.map_values(|x| x as i16);
+    let v = v.map_values(|x| [i32::from(x); 4]);
+    assert!(v.body.iter().map(|x| x[3]).eq(0..10));
+    assert_eq!(count.get(), 1);
+    drop(v);
+    assert_eq!(count.get(), 0);
+}
+
+/// Panics if asked for a zero-size block of memory.
+#[derive(Clone, Copy)]
+struct NoZeroSize;
+
+unsafe impl Allocator for NoZeroSize {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        assert_ne!(layout.size(), 0);
+        Global.allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        assert_ne!(layout.size(), 0);
+        Global.deallocate(ptr, layout)
+    }
+
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        assert_ne!(old_layout.size(), 0);
+        Global.grow(ptr, old_layout, new_layout)
+    }
+
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        assert_ne!(new_layout.size(), 0);
+        Global.shrink(ptr, old_layout, new_layout)
+    }
+}
+
+#[test]
+fn zero_size_layouts_skip_allocator() {
+    let mut v = HeaderVec::<(), u32, _>::with_capacity_in((), 0, NoZeroSize);
+    v.extend(0..10);
+    v.clear();
+    v.push(1);
+    v.truncate(0);
+    v.shrink_to_fit();
+    drop(v);
+    let mut v = HeaderVec::<(), (), _>::new_in((), NoZeroSize);
+    v.extend([(), ()]);
+    let boxed = v.into_box_in();
+    assert_eq!(boxed.len(), 2);
+    assert_eq!(HeaderVec::from_box_in(boxed).len(), 2);
+}
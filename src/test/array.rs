@@ -0,0 +1,75 @@
+use crate::{header_vec, ArrayHeaderVec, CapacityError};
+use alloc::rc::Rc;
+
+static STATIC: ArrayHeaderVec<&str, u8, 4> = ArrayHeaderVec::new("foo");
+
+#[test]
+fn const_new() {
+    const EMPTY: ArrayHeaderVec<u32, u32, 2> = ArrayHeaderVec::new(7);
+    assert_eq!(STATIC.head, "foo");
+    assert!(STATIC.is_empty());
+    assert_eq!(STATIC.capacity(), 4);
+    assert_eq!(EMPTY, header_vec![7u32;]);
+}
+
+#[test]
+fn push_until_full() {
+    let mut v = ArrayHeaderVec::<_, _, 3>::new("foo");
+    assert_eq!(v.push(1), Ok(()));
+    assert_eq!(v.push(2), Ok(()));
+    assert_eq!(v.push(3), Ok(()));
+    assert!(v.is_full());
+    assert_eq!(v.push(4), Err(CapacityError::new(4)));
+    assert_eq!(v.push(5).unwrap_err().into_inner(), 5);
+    assert_eq!(v, header_vec!["foo"; 1, 2, 3]);
+    assert_eq!(v.pop(), Some(3));
+    assert_eq!(v.remaining_capacity(), 1);
+}
+
+#[test]
+fn insert_remove() {
+    let mut v = ArrayHeaderVec::<_, _, 4>::try_from_iter("foo", [1, 3]).unwrap();
+    v.insert(1, 2).unwrap();
+    v.insert(3, 4).unwrap();
+    assert_eq!(v, header_vec!["foo"; 1, 2, 3, 4]);
+    assert_eq!(v.insert(0, 0), Err(CapacityError::new(0)));
+    assert_eq!(v.remove(0), Some(1));
+    assert_eq!(v.remove(3), None);
+    assert_eq!(v.swap_remove(0), Some(2));
+    assert_eq!(v, header_vec!["foo"; 4, 3]);
+}
+
+#[test]
+#[should_panic]
+fn insert_out_of_bounds() {
+    let mut v = ArrayHeaderVec::<_, _, 4>::new(());
+    let _ = v.insert(1, 0);
+}
+
+#[test]
+fn try_extend() {
+    let mut v = ArrayHeaderVec::<_, _, 3>::new(());
+    assert_eq!(v.try_extend(0..5), Err(CapacityError::new(3)));
+    assert_eq!(v, header_vec![(); 0, 1, 2]);
+    v.clear();
+    assert!(v.try_extend_from_slice(&[1, 2, 3, 4]).is_err());
+    assert!(v.is_empty());
+    v.try_extend_from_slice(&[1, 2]).unwrap();
+    assert_eq!(v, header_vec![(); 1, 2]);
+    assert!(ArrayHeaderVec::<(), i32, 1>::try_from_iter((), [1, 2]).is_err());
+}
+
+#[test]
+fn drops_values() {
+    let rc = Rc::new(());
+    let mut v = ArrayHeaderVec::<_, _, 4>::new(rc.clone());
+    v.try_extend([rc.clone(), rc.clone(), rc.clone()]).unwrap();
+    assert_eq!(Rc::strong_count(&rc), 5);
+    v.truncate(1);
+    assert_eq!(Rc::strong_count(&rc), 3);
+    let w = v.clone();
+    assert_eq!(Rc::strong_count(&rc), 5);
+    drop(v);
+    drop(w);
+    assert_eq!(Rc::strong_count(&rc), 1);
+}
@@ -0,0 +1,87 @@
+use crate::{header_vec, HeaderSlice};
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+#[test]
+fn new_box() {
+    let b = HeaderSlice::new_box("foo", alloc::vec![1, 2, 3]);
+    assert_eq!(*b, *header_vec!["foo"; 1, 2, 3]);
+    let b = HeaderSlice::new_box("foo", Vec::<i32>::new());
+    assert_eq!(b.len(), 0);
+}
+
+#[test]
+fn from_slice_box() {
+    let b = HeaderSlice::from_slice_box(1u8, &["a", "b"]);
+    assert_eq!(b.head, 1);
+    assert_eq!(b.body, ["a", "b"]);
+}
+
+#[test]
+fn new_box_with() {
+    let b = HeaderSlice::new_box_with((), 5, |i| i * i);
+    assert_eq!(b.body, [0, 1, 4, 9, 16]);
+    let b = HeaderSlice::new_box_with(0u64, 3, |_| ());
+    assert_eq!(b.len(), 3);
+}
+
+#[test]
+fn new_box_with_panic() {
+    let rc = Rc::new(());
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        HeaderSlice::new_box_with(rc.clone(), 4, |i| {
+            if i == 2 {
+                panic!("new_box_with panicked");
+            }
+            rc.clone()
+        })
+    }));
+    assert!(result.is_err());
+    assert_eq!(Rc::strong_count(&rc), 1);
+}
+
+/// An iterator that reports more values than it yields.
+struct Short<I>(I, usize);
+
+impl<I: Iterator> Iterator for Short<I> {
+    type Item = I::Item;
+    fn next(&mut self) -> Option<I::Item> {
+        self.0.next()
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.1, Some(self.1))
+    }
+}
+
+impl<I: Iterator> ExactSizeIterator for Short<I> {}
+
+#[test]
+fn new_box_short_iter() {
+    let rc = Rc::new(());
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        HeaderSlice::new_box(rc.clone(), Short(alloc::vec![rc.clone()].into_iter(), 3))
+    }));
+    assert!(result.is_err());
+    assert_eq!(Rc::strong_count(&rc), 1);
+
+    // values beyond the reported length are ignored
+    let b = HeaderSlice::new_box("foo", Short(0..5, 2));
+    assert_eq!(*b, *header_vec!["foo"; 0, 1]);
+}
+
+#[test]
+fn clone_box() {
+    let b = header_vec![String::from("foo"); String::from("bar")].into_box();
+    let c = b.clone();
+    assert_eq!(b, c);
+    assert_ne!(b.body.as_ptr(), c.body.as_ptr());
+}
+
+#[test]
+fn default_box() {
+    let b: Box<HeaderSlice<u32, String>> = Default::default();
+    assert_eq!(b.head, 0);
+    assert!(b.is_empty());
+}
@@ -0,0 +1,125 @@
+use crate::{header_vec, CastError, HeaderSlice, HeaderSliceMut, HeaderSliceRef};
+
+#[repr(C, align(8))]
+struct Aligned([u8; 24]);
+
+fn buffer() -> Aligned {
+    let mut buf = Aligned([0; 24]);
+    for (i, b) in buf.0.iter_mut().enumerate() {
+        *b = i as u8;
+    }
+    buf
+}
+
+#[test]
+fn try_from_bytes() {
+    let buf = buffer();
+    let s = HeaderSlice::<u64, u32>::try_from_bytes(&buf.0).unwrap();
+    assert_eq!(s.head, u64::from_ne_bytes([0, 1, 2, 3, 4, 5, 6, 7]));
+    assert_eq!(s.len(), 4);
+    assert_eq!(s.body[3], u32::from_ne_bytes([20, 21, 22, 23]));
+}
+
+#[test]
+fn try_from_bytes_mut() {
+    let mut buf = buffer();
+    let s = HeaderSlice::<u32, u16>::try_from_bytes_mut(&mut buf.0).unwrap();
+    assert_eq!(s.len(), 10);
+    s.head = 0;
+    s.body[0] = 0;
+    assert_eq!(buf.0[..6], [0; 6]);
+}
+
+#[test]
+fn try_from_bytes_errors() {
+    let buf = buffer();
+    assert_eq!(
+        HeaderSlice::<u32, u32>::try_from_bytes(&buf.0[1..13]).unwrap_err(),
+        CastError::Misaligned
+    );
+    assert_eq!(
+        HeaderSlice::<u64, u32>::try_from_bytes(&buf.0[..4]).unwrap_err(),
+        CastError::TooShort
+    );
+    assert_eq!(
+        HeaderSlice::<u32, u32>::try_from_bytes(&buf.0[..10]).unwrap_err(),
+        CastError::InvalidLength
+    );
+    // a body of one `u8` would need 7 bytes of trailing padding
+    assert_eq!(
+        HeaderSlice::<u64, u8>::try_from_bytes(&buf.0[..9]).unwrap_err(),
+        CastError::InvalidLength
+    );
+}
+
+#[test]
+fn view_try_from_bytes() {
+    let buf = buffer();
+    let s = HeaderSliceRef::<u64, u8>::try_from_bytes(&buf.0[..11]).unwrap();
+    assert_eq!(*s.head, u64::from_ne_bytes([0, 1, 2, 3, 4, 5, 6, 7]));
+    assert_eq!(s.body, [8, 9, 10]);
+    assert_eq!(
+        HeaderSliceRef::<u64, u32>::try_from_bytes(&buf.0[..10]).unwrap_err(),
+        CastError::InvalidLength
+    );
+    assert_eq!(
+        HeaderSliceRef::<u64, u32>::try_from_bytes(&buf.0[..4]).unwrap_err(),
+        CastError::TooShort
+    );
+
+    let mut buf = buffer();
+    let s = HeaderSliceMut::<u32, u16>::try_from_bytes_mut(&mut buf.0[..6]).unwrap();
+    assert_eq!(s.len(), 1);
+    *s.head = 0;
+    s.body[0] = 0;
+    assert_eq!(buf.0[..7], [0, 0, 0, 0, 0, 0, 6]);
+}
+
+#[test]
+fn as_bytes() {
+    let v = header_vec![0x0102_0304u32; 0x0506u16, 0x0708];
+    let bytes = v.as_bytes();
+    assert_eq!(bytes.len(), 8);
+    assert_eq!(bytes[..4], 0x0102_0304u32.to_ne_bytes());
+    assert_eq!(bytes[6..], 0x0708u16.to_ne_bytes());
+
+    let buf = buffer();
+    let s = HeaderSlice::<u64, u32>::try_from_bytes(&buf.0).unwrap();
+    assert_eq!(s.as_bytes(), buf.0);
+}
+
+#[test]
+fn as_bytes_round_trip() {
+    // a body of one `u8` is followed by 7 bytes of trailing padding, which are left out
+    let v = header_vec![0x0102_0304_0506_0708u64; 9u8];
+    let bytes = v.as_bytes();
+    assert_eq!(bytes.len(), 9);
+    let mut buf = Aligned([0xff; 24]);
+    buf.0[..9].copy_from_slice(bytes);
+    let s = HeaderSliceRef::<u64, u8>::try_from_bytes(&buf.0[..9]).unwrap();
+    assert_eq!(*s.head, v.head);
+    assert_eq!(s.body, [9]);
+
+    // with the padding added back, the same bytes cast to a `HeaderSlice`
+    let v = header_vec![0x0102_0304_0506_0708u64; [1u16, 2, 3]];
+    let bytes = v.as_bytes();
+    assert_eq!(bytes.len(), 14);
+    let mut buf = Aligned([0xff; 24]);
+    buf.0[..14].copy_from_slice(bytes);
+    let s = HeaderSlice::<u64, [u16; 3]>::try_from_bytes(&buf.0[..16]).unwrap();
+    assert_eq!(*s, *v);
+}
+
+#[test]
+fn as_bytes_truncated() {
+    let mut v = header_vec![1u64; 1u8, 2, 3, 4, 5, 6, 7, 8];
+    assert_eq!(v.as_truncated_mut(1).as_bytes().len(), 9);
+    assert_eq!(v.body, [1, 2, 3, 4, 5, 6, 7, 8]);
+}
+
+#[test]
+#[should_panic]
+fn as_bytes_padding() {
+    let v = header_vec![1u8; 2u32];
+    v.as_bytes();
+}
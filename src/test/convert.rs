@@ -0,0 +1,68 @@
+use crate::{header_vec, HeaderVec};
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+#[test]
+fn from_vec() {
+    let mut src = Vec::with_capacity(10);
+    src.extend_from_slice(&[1u32, 2, 3]);
+    let v = HeaderVec::from_vec("foo", src);
+    assert_eq!(v, header_vec!["foo"; 1, 2, 3]);
+    assert_eq!(v.capacity(), 10);
+
+    let v = HeaderVec::from_vec(7u8, vec![1u64, 2, 3]);
+    assert_eq!(v, header_vec![7; 1, 2, 3]);
+    let v = HeaderVec::from_vec([1u8; 3], vec![1u8, 2, 3]);
+    assert_eq!(v, header_vec![[1; 3]; 1, 2, 3]);
+    let v = HeaderVec::from_vec(1u128, Vec::<u8>::new());
+    assert_eq!(v, header_vec![1;]);
+}
+
+#[test]
+fn from_vec_without_header_keeps_buffer() {
+    let src = vec![1, 2, 3];
+    let ptr = src.as_ptr();
+    let v = HeaderVec::from_vec((), src);
+    assert_eq!(v.body.as_ptr(), ptr);
+    let (_, src) = v.into_head_and_vec();
+    assert_eq!(src.as_ptr(), ptr);
+}
+
+#[test]
+fn into_head_and_vec() {
+    let mut v = HeaderVec::with_capacity(String::from("foo"), 10);
+    v.extend([1u16, 2, 3]);
+    let (head, values) = v.into_head_and_vec();
+    assert_eq!(head, "foo");
+    assert_eq!(values, [1, 2, 3]);
+    assert_eq!(values.capacity(), 10);
+
+    let (head, values) = header_vec![1u64; 1u8, 2, 3].into_head_and_vec();
+    assert_eq!((head, values), (1, vec![1, 2, 3]));
+    let (head, values) = HeaderVec::<u32, u32>::with_capacity(5, 0).into_head_and_vec();
+    assert_eq!((head, values), (5, vec![]));
+    let (head, values) = HeaderVec::from_iter(5u8, [(); 3]).into_head_and_vec();
+    assert_eq!((head, values), (5, vec![(); 3]));
+}
+
+#[test]
+fn owned_values_round_trip() {
+    let values: Vec<_> = (0..20).map(Box::new).collect();
+    let v = HeaderVec::from((String::from("foo"), values));
+    assert_eq!(v.head, "foo");
+    assert!(v.body.iter().map(|x| **x).eq(0..20));
+    let (head, values): (String, Vec<Box<i32>>) = v.into();
+    assert_eq!(head, "foo");
+    assert!(values.into_iter().map(|x| *x).eq(0..20));
+}
+
+#[test]
+fn from_array() {
+    let v = HeaderVec::from(("foo", [1, 2, 3]));
+    assert_eq!(v, header_vec!["foo"; 1, 2, 3]);
+    assert_eq!(v.capacity(), 3);
+    let v = HeaderVec::from(("foo", [0u8; 0]));
+    assert!(v.is_empty());
+}
@@ -0,0 +1,172 @@
+use crate::{header_vec, HeaderSlice, HeaderVec, HeaderVecDeque};
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+
+#[test]
+fn push_pop_both_ends() {
+    let mut d = HeaderVecDeque::with_capacity("foo", 4);
+    d.push_back(2);
+    d.push_back(3);
+    d.push_front(1);
+    d.push_front(0);
+    assert_eq!(d.len(), 4);
+    assert_eq!(d.iter().copied().collect::<Vec<_>>(), [0, 1, 2, 3]);
+    assert_eq!(d.front(), Some(&0));
+    assert_eq!(d.back(), Some(&3));
+    assert_eq!(d.pop_front(), Some(0));
+    assert_eq!(d.pop_back(), Some(3));
+    assert_eq!(d.pop_front(), Some(1));
+    assert_eq!(d.pop_front(), Some(2));
+    assert_eq!(d.pop_front(), None);
+    assert_eq!(d.pop_back(), None);
+    assert_eq!(*d.head(), "foo");
+}
+
+#[test]
+fn grows_while_wrapped() {
+    for front in 0..6 {
+        let mut d = HeaderVecDeque::with_capacity((), 6);
+        let mut expected = Vec::new();
+        for i in 0..6 {
+            if i < front {
+                d.push_front(i);
+                expected.insert(0, i);
+            } else {
+                d.push_back(i);
+                expected.push(i);
+            }
+        }
+        assert_eq!(d.capacity(), 6);
+        for i in 6..20 {
+            d.push_back(i);
+            expected.push(i);
+        }
+        assert_eq!(d.iter().copied().collect::<Vec<_>>(), expected);
+        assert_eq!(d.iter().rev().count(), expected.len());
+    }
+}
+
+#[test]
+fn as_slices_and_make_contiguous() {
+    let mut d = HeaderVecDeque::with_capacity("foo", 4);
+    d.extend([2, 3, 4]);
+    d.pop_front();
+    d.push_back(5);
+    d.push_back(6);
+    assert_eq!(d.as_slices(), (&[3, 4, 5][..], &[6][..]));
+    let slice: &mut HeaderSlice<&str, i32> = d.make_contiguous();
+    assert_eq!(*slice, header_vec!["foo"; 3, 4, 5, 6]);
+    slice.body[0] = 0;
+    assert_eq!(d.as_slices(), (&[0, 4, 5, 6][..], &[][..]));
+    d.push_front(-1);
+    assert_eq!(d[0], -1);
+    assert_eq!(HeaderVec::from(d), header_vec!["foo"; -1, 0, 4, 5, 6]);
+}
+
+#[test]
+fn drain() {
+    for start in 0..5 {
+        for end in start..5 {
+            let mut d = HeaderVecDeque::with_capacity((), 5);
+            d.extend([2, 3, 4]);
+            d.push_front(1);
+            d.push_front(0);
+            let drained: Vec<_> = d.drain(start..end).collect();
+            assert_eq!(drained, (start..end).collect::<Vec<_>>());
+            let rest: Vec<_> = d.into_iter().collect();
+            let expected: Vec<_> = (0..start).chain(end..5).collect();
+            assert_eq!(rest, expected);
+        }
+    }
+}
+
+#[test]
+fn drain_drops_remaining() {
+    let rc = Rc::new(());
+    let mut d = HeaderVecDeque::new(());
+    d.extend(core::iter::repeat(rc.clone()).take(6));
+    let mut drain = d.drain(1..5);
+    drop(drain.next());
+    drop(drain.next_back());
+    drop(drain);
+    assert_eq!(d.len(), 2);
+    assert_eq!(Rc::strong_count(&rc), 3);
+    d.clear();
+    assert_eq!(Rc::strong_count(&rc), 1);
+}
+
+#[test]
+fn drops_values() {
+    let rc = Rc::new(());
+    let mut d = HeaderVecDeque::with_capacity(rc.clone(), 3);
+    d.push_back(rc.clone());
+    d.push_front(rc.clone());
+    d.push_front(rc.clone());
+    let e = d.clone();
+    assert_eq!(Rc::strong_count(&rc), 9);
+    d.truncate(1);
+    assert_eq!(Rc::strong_count(&rc), 7);
+    drop(d);
+    let mut iter = e.into_iter();
+    iter.next();
+    drop(iter);
+    assert_eq!(Rc::strong_count(&rc), 1);
+}
+
+#[test]
+fn from_header_vec() {
+    let v = header_vec!["foo"; 1, 2, 3];
+    let cap = v.capacity();
+    let mut d = HeaderVecDeque::from(v);
+    assert_eq!(d.capacity(), cap);
+    d.push_front(0);
+    *d.head_mut() = "bar";
+    assert_eq!(alloc::format!("{:?}", d), "[\"bar\"; 0, 1, 2, 3]");
+    assert_eq!(d, HeaderVecDeque::from_iter("bar", 0..4));
+}
+
+#[test]
+fn zero_sized() {
+    let mut d = HeaderVecDeque::new(1u8);
+    d.push_front(());
+    d.push_back(());
+    assert_eq!(d.len(), 2);
+    assert_eq!(d.drain(..1).count(), 1);
+    assert_eq!(d.pop_front(), Some(()));
+    assert!(d.is_empty());
+}
+
+/// Records each call to `write` separately, so two values can be checked for hashing with the
+/// same sequence of writes.
+#[derive(Default)]
+struct CallHasher(Vec<Vec<u8>>);
+
+impl core::hash::Hasher for CallHasher {
+    fn finish(&self) -> u64 {
+        0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.push(bytes.to_vec());
+    }
+}
+
+fn hash_calls<T: core::hash::Hash + ?Sized>(x: &T) -> Vec<Vec<u8>> {
+    let mut state = CallHasher::default();
+    x.hash(&mut state);
+    state.0
+}
+
+#[test]
+fn hash_ignores_wrap_point() {
+    let mut d1 = HeaderVecDeque::with_capacity("foo", 4);
+    d1.extend([0u8, 1, 2, 3]);
+    assert!(d1.as_slices().1.is_empty());
+    let mut d2 = HeaderVecDeque::with_capacity("foo", 4);
+    d2.extend([2u8, 3]);
+    d2.push_front(1);
+    d2.push_front(0);
+    assert!(!d2.as_slices().1.is_empty());
+    assert_eq!(d1, d2);
+    assert_eq!(hash_calls(&d1), hash_calls(&d2));
+}
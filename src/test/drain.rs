@@ -0,0 +1,106 @@
+use crate::header_vec;
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+
+#[test]
+fn drain_middle() {
+    let mut v = header_vec!["foo"; 1, 2, 3, 4, 5];
+    let cap = v.capacity();
+    let drained: Vec<_> = v.drain(1..3).collect();
+    assert_eq!(drained, [2, 3]);
+    assert_eq!(v, header_vec!["foo"; 1, 4, 5]);
+    assert_eq!(v.capacity(), cap);
+}
+
+#[test]
+fn drain_bounds() {
+    let mut v = header_vec!["foo"; 1, 2, 3, 4, 5];
+    assert_eq!(v.drain(3..).collect::<Vec<_>>(), [4, 5]);
+    assert_eq!(v.drain(..=0).collect::<Vec<_>>(), [1]);
+    assert_eq!(v.drain(1..1).count(), 0);
+    assert_eq!(v, header_vec!["foo"; 2, 3]);
+    assert_eq!(v.drain(..).collect::<Vec<_>>(), [2, 3]);
+    assert_eq!(v, header_vec!["foo";]);
+}
+
+#[test]
+#[should_panic]
+fn drain_out_of_bounds() {
+    let mut v = header_vec!["foo"; 1, 2, 3];
+    v.drain(2..4);
+}
+
+#[test]
+fn drain_double_ended() {
+    let mut v = header_vec!["foo"; 1, 2, 3, 4, 5, 6];
+    let mut d = v.drain(1..5);
+    assert_eq!(d.len(), 4);
+    assert_eq!(d.next_back(), Some(5));
+    assert_eq!(d.next(), Some(2));
+    assert_eq!(d.as_slice(), [3, 4]);
+    assert_eq!(d.next_back(), Some(4));
+    assert_eq!(d.next_back(), Some(3));
+    assert_eq!(d.next(), None);
+    drop(d);
+    assert_eq!(v, header_vec!["foo"; 1, 6]);
+}
+
+#[test]
+fn drain_drops_unyielded() {
+    let rc = Rc::new(());
+    let mut v = header_vec![(); rc.clone(), rc.clone(), rc.clone(), rc.clone()];
+    let mut d = v.drain(1..3);
+    let first = d.next().unwrap();
+    drop(d);
+    assert_eq!(v.len(), 2);
+    assert_eq!(Rc::strong_count(&rc), 4);
+    drop(first);
+    assert_eq!(Rc::strong_count(&rc), 3);
+}
+
+#[test]
+fn drain_keep_rest() {
+    let mut v = header_vec!["foo"; 1, 2, 3, 4, 5, 6];
+    let mut d = v.drain(1..5);
+    assert_eq!(d.next(), Some(2));
+    assert_eq!(d.next_back(), Some(5));
+    d.keep_rest();
+    assert_eq!(v, header_vec!["foo"; 1, 3, 4, 6]);
+}
+
+#[test]
+fn drain_leak() {
+    let mut v = header_vec!["foo"; 1, 2, 3, 4, 5];
+    core::mem::forget(v.drain(2..4));
+    assert_eq!(v, header_vec!["foo"; 1, 2]);
+}
+
+#[test]
+fn drain_zst() {
+    let mut v = header_vec!["foo"; (); 5];
+    assert_eq!(v.drain(1..4).count(), 3);
+    assert_eq!(v.len(), 2);
+}
+
+#[test]
+fn drain_panic_in_drop() {
+    struct PanicOnDrop<'a>(i32, &'a RefCell<Vec<i32>>);
+    impl Drop for PanicOnDrop<'_> {
+        fn drop(&mut self) {
+            self.1.borrow_mut().push(self.0);
+            if self.0 == 2 {
+                panic!("dropped 2");
+            }
+        }
+    }
+
+    let q = RefCell::new(Vec::new());
+    let mut v = header_vec![(); PanicOnDrop(1, &q), PanicOnDrop(2, &q), PanicOnDrop(3, &q), PanicOnDrop(4, &q)];
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        v.drain(1..3);
+    }));
+    assert!(result.is_err());
+    assert_eq!(*q.borrow(), [2, 3]);
+    assert_eq!(v.body.iter().map(|x| x.0).collect::<Vec<_>>(), [1, 4]);
+}
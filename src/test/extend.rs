@@ -0,0 +1,220 @@
+use crate::{header_vec, HeaderVec};
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cell::Cell;
+
+/// An iterator whose size hint is lower than its real length.
+struct Underestimate<I>(I);
+
+impl<I: Iterator> Iterator for Underestimate<I> {
+    type Item = I::Item;
+    fn next(&mut self) -> Option<I::Item> {
+        self.0.next()
+    }
+}
+
+/// Counts live instances, and panics when cloned once `clones_left` reaches zero.
+struct Tracked<'a> {
+    val: i32,
+    live: &'a Cell<isize>,
+    clones_left: &'a Cell<usize>,
+}
+
+impl<'a> Tracked<'a> {
+    fn new(val: i32, live: &'a Cell<isize>, clones_left: &'a Cell<usize>) -> Self {
+        live.set(live.get() + 1);
+        Self {
+            val,
+            live,
+            clones_left,
+        }
+    }
+}
+
+impl Clone for Tracked<'_> {
+    fn clone(&self) -> Self {
+        let left = self.clones_left.get();
+        if left == 0 {
+            panic!("clone failed");
+        }
+        self.clones_left.set(left - 1);
+        Self::new(self.val, self.live, self.clones_left)
+    }
+}
+
+impl Drop for Tracked<'_> {
+    fn drop(&mut self) {
+        self.live.set(self.live.get() - 1);
+    }
+}
+
+fn vals(v: &HeaderVec<(), Tracked>) -> Vec<i32> {
+    v.body.iter().map(|x| x.val).collect()
+}
+
+#[test]
+fn extend_reserves_from_size_hint() {
+    let mut v = HeaderVec::with_capacity("foo", 0);
+    v.extend(0..1000);
+    assert_eq!(v.capacity(), 1000);
+    assert!(v.body.iter().copied().eq(0..1000));
+}
+
+#[test]
+fn extend_beyond_size_hint() {
+    let mut v = header_vec!["foo"; 1, 2];
+    v.extend(Underestimate(3..100));
+    assert!(v.body.iter().copied().eq(1..100));
+    let v = HeaderVec::from_iter("foo", Underestimate(0..50));
+    assert!(v.body.iter().copied().eq(0..50));
+    let mut v = HeaderVec::new("foo");
+    v.try_extend(Underestimate(0..20)).unwrap();
+    assert!(v.body.iter().copied().eq(0..20));
+}
+
+#[test]
+fn extend_refs() {
+    let mut v = header_vec!["foo"; 1, 2];
+    v.extend(&[3, 4]);
+    v.extend([5, 6].iter());
+    assert_eq!(v, header_vec!["foo"; 1, 2, 3, 4, 5, 6]);
+}
+
+#[test]
+fn extend_from_slice_clone() {
+    let mut v = header_vec!["foo"; String::from("a")];
+    v.extend_from_slice(&[String::from("b"), String::from("c")]);
+    assert_eq!(v.body, ["a", "b", "c"]);
+}
+
+#[test]
+fn extend_from_within() {
+    let mut v = header_vec!["foo"; 1, 2, 3, 4];
+    v.extend_from_within(1..3);
+    assert_eq!(v, header_vec!["foo"; 1, 2, 3, 4, 2, 3]);
+    v.extend_from_within(..);
+    assert_eq!(v.len(), 12);
+    v.extend_from_within(12..);
+    assert_eq!(v.len(), 12);
+}
+
+#[test]
+#[should_panic]
+fn extend_from_within_out_of_bounds() {
+    let mut v = header_vec!["foo"; 1, 2, 3];
+    v.extend_from_within(2..4);
+}
+
+#[test]
+fn resize_with_calls_in_order() {
+    let mut v = header_vec!["foo"; 0];
+    let mut next = 0;
+    v.resize_with(5, || {
+        next += 1;
+        next
+    });
+    assert_eq!(v, header_vec!["foo"; 0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn resize_moves_value_last() {
+    let live = Cell::new(0);
+    let clones_left = Cell::new(2);
+    let mut v = HeaderVec::new(());
+    v.resize(3, Tracked::new(7, &live, &clones_left));
+    assert_eq!(vals(&v), [7, 7, 7]);
+    assert_eq!(clones_left.get(), 0);
+    assert_eq!(live.get(), 3);
+}
+
+#[test]
+fn clone_from_reuses_allocation() {
+    let src = header_vec!["bar"; 1, 2];
+    let mut v = HeaderVec::from_iter("foo", 0..20);
+    let ptr = v.as_ptr().cast::<u8>();
+    v.clone_from(&src);
+    assert_eq!(v, src);
+    assert_eq!(v.as_ptr().cast::<u8>(), ptr);
+
+    let src = HeaderVec::from_iter("baz", 0..10);
+    v.clone_from(&src);
+    assert_eq!(v, src);
+    assert_eq!(v.as_ptr().cast::<u8>(), ptr);
+}
+
+#[test]
+fn clone_from_drops_extra() {
+    let live = Cell::new(0);
+    let clones_left = Cell::new(usize::MAX);
+    let src = HeaderVec::from_iter((), (0..2).map(|i| Tracked::new(i, &live, &clones_left)));
+    let mut v = HeaderVec::from_iter((), (5..10).map(|i| Tracked::new(i, &live, &clones_left)));
+    v.clone_from(&src);
+    assert_eq!(vals(&v), [0, 1]);
+    assert_eq!(live.get(), 4);
+    let src = HeaderVec::from_iter((), (0..6).map(|i| Tracked::new(i, &live, &clones_left)));
+    v.clone_from(&src);
+    assert_eq!(vals(&v), [0, 1, 2, 3, 4, 5]);
+    drop((v, src));
+    assert_eq!(live.get(), 2);
+}
+
+/// Runs `f` on a vector of 3 elements where the third clone panics, then checks that the vector
+/// holds `expected` and nothing leaked or was dropped twice.
+fn check_clone_panic(
+    f: impl for<'a> FnOnce(&mut HeaderVec<(), Tracked<'a>>, &[Tracked<'a>]),
+    expected: &[i32],
+) {
+    let live = Cell::new(0);
+    let clones_left = Cell::new(usize::MAX);
+    let mut v = HeaderVec::from_iter((), (0..3).map(|i| Tracked::new(i, &live, &clones_left)));
+    let src: Vec<_> = (10..15)
+        .map(|i| Tracked::new(i, &live, &clones_left))
+        .collect();
+    clones_left.set(2);
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| f(&mut v, &src)));
+    assert!(result.is_err());
+    assert_eq!(vals(&v), expected);
+    assert_eq!(live.get(), (v.len() + src.len()) as isize);
+    drop((v, src));
+    assert_eq!(live.get(), 0);
+}
+
+#[test]
+fn extend_from_slice_panic() {
+    check_clone_panic(|v, src| v.extend_from_slice(src), &[0, 1, 2, 10, 11]);
+}
+
+#[test]
+fn extend_from_within_panic() {
+    check_clone_panic(|v, _| v.extend_from_within(..), &[0, 1, 2, 0, 1]);
+}
+
+#[test]
+fn resize_panic() {
+    check_clone_panic(|v, src| v.resize(8, src[4].clone()), &[0, 1, 2, 14]);
+}
+
+#[test]
+fn extend_panic() {
+    check_clone_panic(|v, src| v.extend(src.iter().cloned()), &[0, 1, 2, 10, 11]);
+}
+
+#[test]
+fn clone_panic() {
+    check_clone_panic(|v, _| drop(v.clone()), &[0, 1, 2]);
+}
+
+#[test]
+fn clone_from_panic() {
+    let live = Cell::new(0);
+    let clones_left = Cell::new(usize::MAX);
+    let mut v = HeaderVec::from_iter((), (0..3).map(|i| Tracked::new(i, &live, &clones_left)));
+    let src = HeaderVec::from_iter((), (10..15).map(|i| Tracked::new(i, &live, &clones_left)));
+    clones_left.set(2);
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| v.clone_from(&src)));
+    assert!(result.is_err());
+    assert_eq!(vals(&v), [10, 11, 2]);
+    assert_eq!(live.get(), 8);
+    drop((v, src));
+    assert_eq!(live.get(), 0);
+}
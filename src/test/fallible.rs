@@ -0,0 +1,115 @@
+use crate::{header_vec, HeaderVec, TryReserveError};
+
+#[test]
+fn try_with_capacity() {
+    let v = HeaderVec::<_, i32>::try_with_capacity("foo", 10).unwrap();
+    assert_eq!(v.capacity(), 10);
+    assert_eq!(
+        HeaderVec::<_, u64>::try_with_capacity("foo", usize::MAX / 4).unwrap_err(),
+        TryReserveError::LayoutError,
+    );
+}
+
+#[test]
+fn try_reserve() {
+    let mut v = header_vec!["foo"; 1, 2, 3];
+    v.try_reserve(7).unwrap();
+    assert!(v.capacity() >= 10);
+    assert_eq!(
+        v.try_reserve(usize::MAX).unwrap_err(),
+        TryReserveError::CapacityOverflow,
+    );
+    assert_eq!(
+        v.try_reserve(isize::MAX as usize).unwrap_err(),
+        TryReserveError::LayoutError,
+    );
+    assert_eq!(v, header_vec!["foo"; 1, 2, 3]);
+}
+
+#[test]
+fn try_reserve_exact() {
+    let mut v = header_vec!["foo"; 1, 2, 3];
+    v.try_reserve_exact(7).unwrap();
+    assert_eq!(v.capacity(), 10);
+    assert_eq!(
+        v.try_reserve_exact(usize::MAX).unwrap_err(),
+        TryReserveError::CapacityOverflow,
+    );
+    assert_eq!(
+        v.try_reserve_exact(isize::MAX as usize).unwrap_err(),
+        TryReserveError::LayoutError,
+    );
+    assert_eq!(v, header_vec!["foo"; 1, 2, 3]);
+}
+
+#[test]
+fn try_push_and_insert() {
+    let mut v = HeaderVec::new("foo");
+    for i in 0..20 {
+        v.try_push(i).unwrap();
+    }
+    v.try_insert(3, 100).unwrap();
+    v.try_insert(21, 200).unwrap();
+    let mut expected = HeaderVec::from_iter("foo", 0..20);
+    expected.insert(3, 100);
+    expected.push(200);
+    assert_eq!(v, expected);
+}
+
+#[test]
+fn try_extend() {
+    let mut v = header_vec!["foo"; 1, 2];
+    v.try_extend(3..=5).unwrap();
+    assert_eq!(v, header_vec!["foo"; 1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn try_from_iter() {
+    let v = HeaderVec::try_from_iter("foo", 0..4).unwrap();
+    assert_eq!(v, header_vec!["foo"; 0, 1, 2, 3]);
+}
+
+#[test]
+#[should_panic(expected = "capacity overflow")]
+fn reserve_overflow_panics() {
+    let mut v = header_vec!["foo"; 1, 2, 3];
+    v.reserve(usize::MAX);
+}
+
+#[cfg(feature = "allocator-api2")]
+#[test]
+fn try_reserve_alloc_error() {
+    use allocator_api2::alloc::{AllocError, Allocator, Global, Layout};
+    use core::ptr::NonNull;
+
+    /// Refuses any allocation larger than 64 bytes.
+    struct Small;
+
+    unsafe impl Allocator for Small {
+        fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+            if layout.size() > 64 {
+                return Err(AllocError);
+            }
+            Global.allocate(layout)
+        }
+
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+            Global.deallocate(ptr, layout)
+        }
+    }
+
+    let mut v = HeaderVec::<u64, u64, _>::with_capacity_in(0, 2, Small);
+    v.push(1);
+    let err = v.try_reserve_exact(100).unwrap_err();
+    assert_eq!(
+        err,
+        TryReserveError::AllocError {
+            layout: Layout::from_size_align(816, 8).unwrap()
+        },
+    );
+    assert!(matches!(
+        HeaderVec::<u64, u64, _>::try_with_capacity_in(0, 100, Small),
+        Err(TryReserveError::AllocError { .. }),
+    ));
+    assert_eq!(v, header_vec![0; 1]);
+}
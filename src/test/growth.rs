@@ -0,0 +1,89 @@
+use crate::{Global, HeaderVec, NeverShrink};
+
+/// Counts how many times the capacity changes while running `f` on each index.
+fn count_reallocs<P: crate::GrowthPolicy>(
+    v: &mut HeaderVec<(), u32, Global, P>,
+    n: u32,
+    mut f: impl FnMut(&mut HeaderVec<(), u32, Global, P>, u32),
+) -> usize {
+    let mut count = 0;
+    for i in 0..n {
+        let cap = v.capacity();
+        f(v, i);
+        if v.capacity() != cap {
+            count += 1;
+        }
+    }
+    count
+}
+
+#[test]
+fn insert_is_amortized() {
+    let mut v = HeaderVec::new(());
+    let reallocs = count_reallocs(&mut v, 10_000, |v, i| v.insert(0, i));
+    assert!(reallocs <= 12, "{} reallocations", reallocs);
+    assert_eq!(v.len(), 10_000);
+    assert!(v.body.iter().rev().copied().eq(0..10_000));
+}
+
+#[test]
+fn push_pop_hysteresis() {
+    let mut v = HeaderVec::from_iter((), 0..64);
+    let cap = v.capacity();
+    let reallocs = count_reallocs(&mut v, 1000, |v, i| {
+        if i % 2 == 0 {
+            v.pop();
+        } else {
+            v.push(i);
+        }
+    });
+    assert_eq!(reallocs, 0);
+    assert_eq!(v.capacity(), cap);
+
+    // removing elements one at a time only reallocates a logarithmic number of times
+    let reallocs = count_reallocs(&mut v, 64, |v, _| {
+        v.remove(0);
+    });
+    assert!(reallocs <= 4, "{} reallocations", reallocs);
+    assert!(v.is_empty());
+}
+
+#[test]
+fn shrinks_after_mass_removal() {
+    let mut v = HeaderVec::from_iter((), 0..1000);
+    v.truncate(10);
+    assert!(v.capacity() < 100);
+    assert_eq!(v.body, (0..10).collect::<alloc::vec::Vec<_>>()[..]);
+}
+
+#[test]
+fn never_shrink() {
+    let mut v: HeaderVec<(), u32, Global, NeverShrink> = HeaderVec::new(()).with_growth_policy();
+    v.extend(0..1000);
+    let cap = v.capacity();
+    v.truncate(1);
+    v.swap_remove(0);
+    assert_eq!(v.pop(), None);
+    v.extend(0..500);
+    v.retain(|x| *x < 3);
+    v.drain(..);
+    assert_eq!(v.capacity(), cap);
+
+    v.extend(0..5);
+    v.shrink_to(100);
+    assert_eq!(v.capacity(), 100);
+    v.shrink_to(200);
+    assert_eq!(v.capacity(), 100);
+    v.shrink_to(0);
+    assert_eq!(v.capacity(), 5);
+    assert_eq!(v.body, [0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn append_across_policies() {
+    let mut a = HeaderVec::new(()).with_growth_policy::<NeverShrink>();
+    let mut b = HeaderVec::from_iter((), 0..3);
+    a.append(&mut b);
+    assert_eq!(a.body, [0, 1, 2]);
+    assert!(b.is_empty());
+}
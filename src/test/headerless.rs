@@ -0,0 +1,68 @@
+use crate::{header_vec, HeaderSlice, HeaderVec};
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+
+#[test]
+fn from_slice() {
+    let src = [1, 2, 3];
+    let s = HeaderSlice::from_slice(&src);
+    assert_eq!(s.head, ());
+    assert_eq!(s.body, src);
+    assert_eq!(core::mem::size_of_val(s), core::mem::size_of_val(&src));
+
+    let mut src = [1, 2, 3];
+    let s: &mut HeaderSlice<(), i32> = (&mut src[..]).into();
+    s.body[1] = 20;
+    assert_eq!(src, [1, 20, 3]);
+}
+
+#[test]
+fn boxed_slice_round_trip() {
+    let b: Box<[i32]> = vec![1, 2, 3].into_boxed_slice();
+    let ptr = b.as_ptr();
+    let h: Box<HeaderSlice<(), i32>> = b.into();
+    assert_eq!(h.body.as_ptr(), ptr);
+    assert_eq!(*h, *header_vec![(); 1, 2, 3]);
+    let b: Box<[i32]> = h.into();
+    assert_eq!(b.as_ptr(), ptr);
+    assert_eq!(*b, [1, 2, 3]);
+}
+
+#[test]
+fn vec_round_trip() {
+    let mut src = Vec::with_capacity(10);
+    src.extend_from_slice(&[1, 2, 3]);
+    let ptr = src.as_ptr();
+    let mut v = HeaderVec::from(src);
+    assert_eq!(v.capacity(), 10);
+    assert_eq!(v.body.as_ptr(), ptr);
+    v.push(4);
+    let out = Vec::from(v);
+    assert_eq!(out, [1, 2, 3, 4]);
+    assert_eq!(out.as_ptr(), ptr);
+}
+
+#[test]
+fn vec_round_trip_empty() {
+    let v = HeaderVec::from(Vec::<u64>::new());
+    assert_eq!(v.len(), 0);
+    let mut out = Vec::from(v);
+    out.push(1);
+    assert_eq!(out, [1]);
+
+    // the empty vector has no allocation, so dropping and growing must not touch the allocator
+    drop(HeaderVec::from(Vec::<u64>::new()));
+    let mut v = HeaderVec::from(Vec::<u64>::new());
+    assert_eq!(v.capacity(), 0);
+    v.push(1);
+    v.extend_from_slice(&[2, 3]);
+    assert_eq!(v.body, [1, 2, 3]);
+    v.truncate(0);
+    v.shrink_to_fit();
+    assert_eq!(Vec::from(v), []);
+
+    let v = HeaderVec::from(vec![(); 4]);
+    assert_eq!(v.len(), 4);
+    assert_eq!(Vec::from(v).len(), 4);
+}
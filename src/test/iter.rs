@@ -0,0 +1,105 @@
+use crate::{header_vec, HeaderSlice, HeaderVec};
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+
+#[test]
+fn into_iter_by_value() {
+    let v = header_vec!["foo"; 1, 2, 3];
+    let mut out = Vec::new();
+    for x in v {
+        out.push(x);
+    }
+    assert_eq!(out, [1, 2, 3]);
+}
+
+#[test]
+fn into_iter_by_ref() {
+    let mut v = header_vec!["foo"; 1, 2, 3];
+    for x in &mut v {
+        *x *= 2;
+    }
+    assert_eq!((&v).into_iter().copied().collect::<Vec<_>>(), [2, 4, 6]);
+
+    let slice: &mut HeaderSlice<&str, i32> = &mut v;
+    for x in &mut *slice {
+        *x += 1;
+    }
+    let slice: &HeaderSlice<&str, i32> = slice;
+    assert_eq!(slice.into_iter().sum::<i32>(), 15);
+}
+
+#[test]
+fn values_double_ended() {
+    let mut iter = header_vec!["foo"; 1, 2, 3, 4, 5].into_values();
+    assert_eq!(iter.next_back(), Some(5));
+    assert_eq!(iter.next(), Some(1));
+    assert_eq!(iter.as_slice(), [2, 3, 4]);
+    iter.as_mut_slice()[0] = 20;
+    assert_eq!(iter.len(), 3);
+    assert_eq!(iter.by_ref().rev().collect::<Vec<_>>(), [4, 3, 20]);
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next_back(), None);
+}
+
+#[test]
+fn values_head() {
+    let mut iter = header_vec!["foo"; 1, 2, 3].into_iter();
+    assert_eq!(iter.head(), Some(&"foo"));
+    iter.next();
+    assert_eq!(iter.take_head(), Some("foo"));
+    assert_eq!(iter.head(), None);
+    assert_eq!(iter.take_head(), None);
+
+    let (head, mut iter) = header_vec!["foo"; 1, 2, 3].into_header_values();
+    assert_eq!(head, "foo");
+    assert_eq!(iter.take_head(), None);
+
+    let iter = header_vec!["foo"; 1, 2, 3].into_values();
+    assert_eq!(iter.head(), None);
+}
+
+#[test]
+fn values_drop_head() {
+    let rc = Rc::new(());
+    let mut iter = header_vec![rc.clone(); rc.clone(), rc.clone()].into_iter();
+    iter.next();
+    assert_eq!(Rc::strong_count(&rc), 3);
+    drop(iter);
+    assert_eq!(Rc::strong_count(&rc), 1);
+}
+
+#[test]
+fn values_into_header_vec() {
+    let v = header_vec!["foo"; 1, 2, 3, 4, 5];
+    let ptr = v.as_ptr().cast::<u8>();
+    let mut iter = v.into_values();
+    iter.next();
+    iter.next_back();
+    let v: HeaderVec<&str, i32> = iter.into_header_vec("bar");
+    assert_eq!(v, header_vec!["bar"; 2, 3, 4]);
+    assert_eq!(v.as_ptr().cast::<u8>(), ptr);
+}
+
+#[test]
+fn values_into_header_vec_drops() {
+    let rc = Rc::new(());
+    let mut iter = header_vec![rc.clone(); rc.clone(), rc.clone(), rc.clone()].into_iter();
+    drop(iter.next());
+    let v = iter.into_header_vec(rc.clone());
+    assert_eq!(v.len(), 2);
+    assert_eq!(Rc::strong_count(&rc), 4);
+    drop(v);
+    assert_eq!(Rc::strong_count(&rc), 1);
+}
+
+#[test]
+fn values_debug() {
+    let mut iter = header_vec!["foo"; 1, 2, 3].into_iter();
+    iter.next();
+    assert_eq!(alloc::format!("{:?}", iter), "IntoValuesIter([2, 3])");
+
+    // the header isn't printed, so it doesn't need to implement `Debug`
+    struct NoDebug;
+    let iter = header_vec![NoDebug; 1, 2].into_iter();
+    assert_eq!(alloc::format!("{:?}", iter), "IntoValuesIter([1, 2])");
+}
@@ -0,0 +1,158 @@
+use crate::{header_vec, HeaderVec};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::cell::Cell;
+
+std::thread_local! {
+    static LIVE: Cell<isize> = const { Cell::new(0) };
+}
+
+fn live() -> isize {
+    LIVE.with(|x| x.get())
+}
+
+/// Counts how many instances are alive on the current thread. Has the alignment of `T`.
+struct Live<T>(T);
+
+impl<T> Live<T> {
+    fn new(val: T) -> Self {
+        LIVE.with(|x| x.set(x.get() + 1));
+        Self(val)
+    }
+}
+
+impl<T> Drop for Live<T> {
+    fn drop(&mut self) {
+        LIVE.with(|x| x.set(x.get() - 1));
+    }
+}
+
+fn addr<H, T>(v: &HeaderVec<H, T>) -> *const u8 {
+    v.as_ptr().as_ptr() as *const u8
+}
+
+#[test]
+fn map_head_same_layout() {
+    let v = header_vec![7u32; 1u16, 2, 3];
+    let ptr = addr(&v);
+    let v = v.map_head(|h| h as i32 - 10);
+    assert_eq!(v, header_vec![-3i32; 1u16, 2, 3]);
+    assert_eq!(addr(&v), ptr);
+}
+
+#[test]
+fn map_head_resizes() {
+    let v = HeaderVec::from_iter(1u8, 0..100u16);
+    let v = v.map_head(|h| [u32::from(h); 5]);
+    assert_eq!(v.head, [1; 5]);
+    assert!(v.body.iter().copied().eq(0..100));
+    let v = v.map_head(|h| h[0] as u8 + 1);
+    assert_eq!(v.head, 2);
+    assert!(v.body.iter().copied().eq(0..100));
+}
+
+#[test]
+fn map_head_changes_alignment() {
+    let v = HeaderVec::from_iter(1u8, 0..50u8);
+    let v = v.map_head(|h| u64::from(h) << 40);
+    assert_eq!(v.head, 1 << 40);
+    assert!(v.body.iter().copied().eq(0..50));
+    let v = v.map_head(|h| (h >> 40) as u8);
+    assert_eq!(v.head, 1);
+    assert!(v.body.iter().copied().eq(0..50));
+}
+
+#[test]
+fn map_head_empty() {
+    let v = HeaderVec::<[u8; 3], u32>::with_capacity([1, 2, 3], 0);
+    let v = v.map_head(|h| h.len());
+    assert_eq!(v.head, 3);
+    assert!(v.is_empty());
+    let v = HeaderVec::<u8, ()>::from_iter(1, [(); 4]).map_head(u64::from);
+    assert_eq!((v.head, v.len()), (1, 4));
+}
+
+#[test]
+fn map_head_panic() {
+    let before = live();
+    let v = HeaderVec::from_iter(Live::new(0), (1..5).map(Live::new));
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        v.map_head(|h| -> u8 {
+            drop(h);
+            panic!("map_head");
+        })
+    }));
+    assert!(result.is_err());
+    assert_eq!(live(), before);
+}
+
+#[test]
+fn map_values_in_place() {
+    let v = header_vec!["foo"; String::from("a"), String::from("bc")];
+    let ptr = addr(&v);
+    let v = v.map_values(String::into_bytes);
+    assert_eq!(v.body, [b"a".to_vec(), b"bc".to_vec()]);
+    assert_eq!(addr(&v), ptr);
+
+    let v = v.map_values(|x| x.len() as u64);
+    assert_eq!(v.body, [1, 2]);
+}
+
+#[test]
+fn map_values_changes_alignment() {
+    let v = HeaderVec::from_iter(9u8, 0..20u64);
+    let v = v.map_values(u64::to_le_bytes);
+    assert_eq!(v.head, 9);
+    assert!(v.body.iter().map(|x| u64::from_le_bytes(*x)).eq(0..20));
+    let v = v.map_values(u64::from_le_bytes);
+    assert_eq!(v.head, 9);
+    assert!(v.body.iter().copied().eq(0..20));
+}
+
+#[test]
+fn map_values_resizes() {
+    let v = HeaderVec::from_iter("foo", 0..30u8);
+    let v = v.map_values(|x| x.to_string());
+    assert!(v.body.iter().map(|x| x.parse::<u8>().unwrap()).eq(0..30));
+    let v = v.map_values(|x| x.len() as u8);
+    assert_eq!(v.len(), 30);
+    assert_eq!(v.capacity(), 30);
+    let v = v.map_values(|_| ());
+    assert_eq!((v.head, v.len()), ("foo", 30));
+    let v = v.map_values(|()| 1u16);
+    assert_eq!(v.body, [1; 30]);
+    let v = HeaderVec::<u8, u8>::with_capacity(0, 0).map_values(u32::from);
+    assert!(v.is_empty());
+}
+
+/// Maps a vector of 9 elements with `f`, panicking on the fifth, and checks that every element
+/// and the header were dropped exactly once.
+fn check_map_values_panic<T, U>(val: impl Fn(u8) -> T, f: impl Fn(Live<T>) -> U) {
+    let before = live();
+    let v = HeaderVec::from_iter(Live::new(0u8), (1..10).map(|i| Live::new(val(i))));
+    let mut count = 0;
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        v.map_values(|x| {
+            count += 1;
+            if count == 5 {
+                panic!("map_values");
+            }
+            f(x)
+        })
+    }));
+    assert!(result.is_err());
+    assert_eq!(live(), before);
+}
+
+#[test]
+fn map_values_panic() {
+    // same size and alignment
+    check_map_values_panic(u64::from, |x| Live::new(x.0 as i64));
+    // same size, smaller alignment
+    check_map_values_panic(u64::from, |x| Live::new(x.0.to_le_bytes()));
+    // same size, larger alignment
+    check_map_values_panic(|i| [i; 8], |x| Live::new(u64::from_le_bytes(x.0)));
+    // different size
+    check_map_values_panic(u64::from, |x| Live::new(x.0 as u8));
+    check_map_values_panic(u64::from, |x| Live::new(Vec::from([x.0; 3])));
+}
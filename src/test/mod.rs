@@ -1,8 +1,37 @@
 pub mod add;
+#[cfg(feature = "allocator-api2")]
+pub mod allocator;
+pub mod array;
+pub mod boxed;
+#[cfg(feature = "bytemuck")]
+pub mod bytes;
 pub mod cmp;
+pub mod convert;
+#[allow(clippy::bool_assert_comparison)]
 pub mod create_vec;
+pub mod deque;
+pub mod drain;
 pub mod drop;
+pub mod extend;
+pub mod fallible;
+pub mod growth;
+pub mod headerless;
 pub mod insert;
+pub mod iter;
+pub mod map;
 pub mod misc;
+pub mod rc;
 pub mod remove;
 pub mod resize;
+pub mod retain;
+#[cfg(feature = "serde")]
+pub mod serde;
+pub mod shared;
+pub mod small;
+pub mod soa;
+pub mod splice;
+pub mod split;
+pub mod string;
+pub mod thin;
+pub mod view;
+pub mod zst;
@@ -0,0 +1,154 @@
+use crate::{header_vec, HeaderArc, HeaderRc, HeaderVec};
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::mem;
+
+#[test]
+fn one_pointer_wide() {
+    assert_eq!(
+        mem::size_of::<HeaderRc<&str, i32>>(),
+        mem::size_of::<usize>()
+    );
+    assert_eq!(
+        mem::size_of::<HeaderArc<&str, i32>>(),
+        mem::size_of::<usize>()
+    );
+}
+
+#[test]
+fn from_header_vec() {
+    let rc = HeaderRc::from(header_vec!["foo"; 1, 2, 3]);
+    assert_eq!(rc, header_vec!["foo"; 1, 2, 3]);
+    let arc = HeaderArc::from(header_vec![1u8; 2u8, 3]);
+    assert_eq!(arc, header_vec![1u8; 2u8, 3]);
+    let arc = HeaderArc::from(header_vec![1u128; 2u8, 3]);
+    assert_eq!(arc, header_vec![1u128; 2u8, 3]);
+}
+
+#[test]
+fn from_iter() {
+    let rc = HeaderRc::from_iter("foo", 0..5);
+    assert_eq!(rc, header_vec!["foo"; 0, 1, 2, 3, 4]);
+    let arc: HeaderArc<bool, _> = (0..5).collect();
+    assert_eq!(arc, header_vec![false; 0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn counts() {
+    let rc = HeaderRc::from_iter("foo", 0..5);
+    let rc2 = rc.clone();
+    let weak = HeaderRc::downgrade(&rc);
+    assert_eq!(HeaderRc::strong_count(&rc), 2);
+    assert_eq!(HeaderRc::weak_count(&rc), 1);
+    assert!(HeaderRc::ptr_eq(&rc, &rc2));
+    mem::drop(rc);
+    assert_eq!(weak.strong_count(), 1);
+    assert_eq!(weak.upgrade().unwrap(), header_vec!["foo"; 0, 1, 2, 3, 4]);
+    mem::drop(rc2);
+    assert_eq!(weak.strong_count(), 0);
+    assert_eq!(weak.weak_count(), 0);
+    assert!(weak.upgrade().is_none());
+}
+
+#[test]
+fn get_mut() {
+    let mut arc = HeaderArc::from_iter("foo", 0..3);
+    HeaderArc::get_mut(&mut arc).unwrap().body[1] = 7;
+    let arc2 = arc.clone();
+    assert!(HeaderArc::get_mut(&mut arc).is_none());
+    mem::drop(arc2);
+    let weak = HeaderArc::downgrade(&arc);
+    assert!(HeaderArc::get_mut(&mut arc).is_none());
+    mem::drop(weak);
+    assert!(HeaderArc::get_mut(&mut arc).is_some());
+    assert_eq!(arc, header_vec!["foo"; 0, 7, 2]);
+}
+
+#[test]
+fn make_mut() {
+    let mut rc = HeaderRc::from_iter("foo", 0..3);
+    let rc2 = rc.clone();
+    HeaderRc::make_mut(&mut rc).body[0] = 9;
+    assert!(!HeaderRc::ptr_eq(&rc, &rc2));
+    assert_eq!(rc, header_vec!["foo"; 9, 1, 2]);
+    assert_eq!(rc2, header_vec!["foo"; 0, 1, 2]);
+    let ptr = HeaderRc::as_ptr(&rc);
+    HeaderRc::make_mut(&mut rc).head = "bar";
+    assert_eq!(HeaderRc::as_ptr(&rc), ptr);
+    assert_eq!(rc, header_vec!["bar"; 9, 1, 2]);
+}
+
+/// A value that panics if it is cloned.
+#[derive(PartialEq, Debug)]
+struct NoClone(i32);
+
+impl Clone for NoClone {
+    fn clone(&self) -> Self {
+        panic!("cloned")
+    }
+}
+
+#[test]
+fn make_mut_with_weak() {
+    let mut rc = HeaderRc::from_iter("foo", (0..3).map(NoClone));
+    let weak = HeaderRc::downgrade(&rc);
+    HeaderRc::make_mut(&mut rc).body[0] = NoClone(9);
+    assert!(weak.upgrade().is_none());
+    assert_eq!(weak.strong_count(), 0);
+    assert_eq!(HeaderRc::weak_count(&rc), 0);
+    assert_eq!(rc.body, [NoClone(9), NoClone(1), NoClone(2)]);
+
+    let mut arc = HeaderArc::from_iter("foo", (0..3).map(NoClone));
+    let weak = HeaderArc::downgrade(&arc);
+    HeaderArc::make_mut(&mut arc).head = "bar";
+    assert!(weak.upgrade().is_none());
+    assert_eq!(arc.head, "bar");
+    assert_eq!(arc.body, [NoClone(0), NoClone(1), NoClone(2)]);
+}
+
+#[test]
+fn try_unwrap() {
+    let arc = HeaderArc::from_iter("foo", 0..3);
+    let arc2 = arc.clone();
+    let arc = HeaderArc::try_unwrap(arc).unwrap_err();
+    mem::drop(arc2);
+    let v: HeaderVec<_, _> = HeaderArc::try_unwrap(arc).unwrap();
+    assert_eq!(v, header_vec!["foo"; 0, 1, 2]);
+}
+
+#[test]
+fn try_unwrap_with_weak() {
+    let rc = HeaderRc::from_iter("foo", 0..3);
+    let weak = HeaderRc::downgrade(&rc);
+    let v = HeaderRc::try_unwrap(rc).unwrap();
+    assert!(weak.upgrade().is_none());
+    assert_eq!(v, header_vec!["foo"; 0, 1, 2]);
+}
+
+#[test]
+fn drops_once() {
+    let q = RefCell::new(Vec::new());
+    struct A<'a>(i32, &'a RefCell<Vec<i32>>);
+    impl Drop for A<'_> {
+        fn drop(&mut self) {
+            self.1.borrow_mut().push(self.0);
+        }
+    }
+    let rc = HeaderRc::from_iter(A(0, &q), (1..=3).map(|i| A(i, &q)));
+    let weak = HeaderRc::downgrade(&rc);
+    let rc2 = rc.clone();
+    mem::drop(rc);
+    assert!(q.borrow().is_empty());
+    mem::drop(rc2);
+    assert_eq!(**q.borrow(), [0, 1, 2, 3]);
+    mem::drop(weak);
+    assert_eq!(**q.borrow(), [0, 1, 2, 3]);
+}
+
+#[test]
+fn arc_across_threads() {
+    fn assert_send_sync<X: Send + Sync>(_: &X) {}
+    let arc = HeaderArc::from_iter("foo", 0..3);
+    assert_send_sync(&arc);
+    assert_send_sync(&HeaderArc::downgrade(&arc));
+}
@@ -0,0 +1,105 @@
+use crate::header_vec;
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+
+#[test]
+fn retain() {
+    let mut v = header_vec!["foo"; 1, 2, 3, 4, 5, 6, 7];
+    v.retain(|x| x % 2 == 1);
+    assert_eq!(v, header_vec!["foo"; 1, 3, 5, 7]);
+    v.retain(|_| true);
+    assert_eq!(v, header_vec!["foo"; 1, 3, 5, 7]);
+    v.retain(|_| false);
+    assert_eq!(v, header_vec!["foo";]);
+}
+
+#[test]
+fn retain_mut() {
+    let mut v = header_vec!["foo"; 1, 2, 3, 4];
+    v.retain_mut(|x| {
+        *x *= 10;
+        *x != 20
+    });
+    assert_eq!(v, header_vec!["foo"; 10, 30, 40]);
+}
+
+#[test]
+fn retain_drops_removed() {
+    let rc = Rc::new(());
+    let mut v = header_vec![(); rc.clone(), rc.clone(), rc.clone(), rc.clone()];
+    let mut i = 0;
+    v.retain(|_| {
+        i += 1;
+        i % 2 == 0
+    });
+    assert_eq!(v.len(), 2);
+    assert_eq!(Rc::strong_count(&rc), 3);
+}
+
+#[test]
+fn retain_panic() {
+    let rc = Rc::new(());
+    let mut v = header_vec![(); (0, rc.clone()), (1, rc.clone()), (2, rc.clone()), (3, rc.clone())];
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        v.retain(|x| {
+            if x.0 == 2 {
+                panic!("retain panicked");
+            }
+            x.0 != 1
+        });
+    }));
+    assert!(result.is_err());
+    assert_eq!(v.body.iter().map(|x| x.0).collect::<Vec<_>>(), [0, 2, 3]);
+    assert_eq!(Rc::strong_count(&rc), 4);
+    drop(v);
+    assert_eq!(Rc::strong_count(&rc), 1);
+}
+
+#[test]
+fn retain_shrinks() {
+    let mut v = header_vec!["foo"; 0; 64];
+    assert!(v.capacity() >= 64);
+    v.retain(|_| false);
+    assert!(v.capacity() < 64);
+}
+
+#[test]
+fn dedup() {
+    let mut v = header_vec!["foo"; 1, 1, 2, 3, 3, 3, 1, 4, 4];
+    v.dedup();
+    assert_eq!(v, header_vec!["foo"; 1, 2, 3, 1, 4]);
+    let mut v = header_vec!["foo"; 1];
+    v.dedup();
+    assert_eq!(v, header_vec!["foo"; 1]);
+}
+
+#[test]
+fn dedup_by_key() {
+    let mut v = header_vec!["foo"; 10, 11, 20, 21, 22, 30, 12];
+    v.dedup_by_key(|x| *x / 10);
+    assert_eq!(v, header_vec!["foo"; 10, 20, 30, 12]);
+}
+
+#[test]
+fn dedup_by() {
+    let mut v = header_vec!["foo"; "a", "A", "b", "c", "C", "c"];
+    v.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+    assert_eq!(v, header_vec!["foo"; "a", "b", "c"]);
+}
+
+#[test]
+fn dedup_panic() {
+    let rc = Rc::new(());
+    let mut v = header_vec![(); (0, rc.clone()), (0, rc.clone()), (1, rc.clone()), (1, rc.clone())];
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        v.dedup_by(|a, b| {
+            if a.0 == 1 {
+                panic!("dedup panicked");
+            }
+            a.0 == b.0
+        });
+    }));
+    assert!(result.is_err());
+    assert_eq!(v.body.iter().map(|x| x.0).collect::<Vec<_>>(), [0, 1, 1]);
+    assert_eq!(Rc::strong_count(&rc), 4);
+}
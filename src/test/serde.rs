@@ -0,0 +1,165 @@
+use crate::{header_vec, HeaderSlice, HeaderVec};
+use alloc::boxed::Box;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_test::{assert_de_tokens, assert_de_tokens_error, assert_tokens, Token};
+
+#[derive(Debug, PartialEq)]
+struct AsStruct(HeaderVec<u8, i32>);
+
+impl Serialize for AsStruct {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        crate::serde::as_struct::serialize(&self.0, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for AsStruct {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        crate::serde::as_struct::deserialize(deserializer).map(AsStruct)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct AsBytes(Box<HeaderSlice<u8, u8>>);
+
+impl Serialize for AsBytes {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        crate::serde::bytes::serialize(&self.0, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for AsBytes {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        crate::serde::bytes::deserialize(deserializer).map(AsBytes)
+    }
+}
+
+#[test]
+fn tuple_form() {
+    let v: HeaderVec<u8, i32> = header_vec![7; 1, 2, 3];
+    assert_tokens(
+        &v,
+        &[
+            Token::Tuple { len: 2 },
+            Token::U8(7),
+            Token::Seq { len: Some(3) },
+            Token::I32(1),
+            Token::I32(2),
+            Token::I32(3),
+            Token::SeqEnd,
+            Token::TupleEnd,
+        ],
+    );
+}
+
+#[test]
+fn tuple_form_box() {
+    let b: Box<HeaderSlice<u8, i32>> = header_vec![7; 1, 2].into_box();
+    assert_tokens(
+        &b,
+        &[
+            Token::Tuple { len: 2 },
+            Token::U8(7),
+            Token::Seq { len: Some(2) },
+            Token::I32(1),
+            Token::I32(2),
+            Token::SeqEnd,
+            Token::TupleEnd,
+        ],
+    );
+}
+
+#[test]
+fn tuple_form_missing_body() {
+    assert_de_tokens_error::<HeaderVec<u8, i32>>(
+        &[Token::Tuple { len: 1 }, Token::U8(7), Token::TupleEnd],
+        "invalid length 1, expected a tuple of size 2",
+    );
+}
+
+#[test]
+fn struct_form() {
+    let v = AsStruct(header_vec![7; 1, 2]);
+    assert_tokens(
+        &v,
+        &[
+            Token::Struct {
+                name: "HeaderSlice",
+                len: 2,
+            },
+            Token::Str("head"),
+            Token::U8(7),
+            Token::Str("body"),
+            Token::Seq { len: Some(2) },
+            Token::I32(1),
+            Token::I32(2),
+            Token::SeqEnd,
+            Token::StructEnd,
+        ],
+    );
+}
+
+#[test]
+fn struct_form_body_first() {
+    let v = AsStruct(header_vec![7; 1, 2]);
+    assert_de_tokens(
+        &v,
+        &[
+            Token::Struct {
+                name: "HeaderSlice",
+                len: 2,
+            },
+            Token::Str("body"),
+            Token::Seq { len: Some(2) },
+            Token::I32(1),
+            Token::I32(2),
+            Token::SeqEnd,
+            Token::Str("head"),
+            Token::U8(7),
+            Token::StructEnd,
+        ],
+    );
+}
+
+#[test]
+fn struct_form_missing_head() {
+    assert_de_tokens_error::<AsStruct>(
+        &[
+            Token::Struct {
+                name: "HeaderSlice",
+                len: 1,
+            },
+            Token::Str("body"),
+            Token::Seq { len: Some(0) },
+            Token::SeqEnd,
+            Token::StructEnd,
+        ],
+        "missing field `head`",
+    );
+}
+
+#[test]
+fn bytes_form() {
+    let b = AsBytes(HeaderVec::copy_from_slice(7, b"abc").into_box());
+    assert_tokens(
+        &b,
+        &[
+            Token::Tuple { len: 2 },
+            Token::U8(7),
+            Token::Bytes(b"abc"),
+            Token::TupleEnd,
+        ],
+    );
+    assert_de_tokens(
+        &b,
+        &[
+            Token::Tuple { len: 2 },
+            Token::U8(7),
+            Token::Seq { len: Some(3) },
+            Token::U8(b'a'),
+            Token::U8(b'b'),
+            Token::U8(b'c'),
+            Token::SeqEnd,
+            Token::TupleEnd,
+        ],
+    );
+}
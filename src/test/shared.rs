@@ -0,0 +1,57 @@
+use crate::{header_vec, HeaderSlice, HeaderVec};
+use alloc::rc::Rc;
+use alloc::sync::Arc;
+
+#[test]
+fn rc_from_header_vec() {
+    let rc: Rc<HeaderSlice<&str, i32>> = header_vec!["foo"; 1, 2, 3].into();
+    assert_eq!(rc.head, "foo");
+    assert_eq!(rc.body, [1, 2, 3]);
+}
+
+#[test]
+fn arc_from_box() {
+    let arc: Arc<HeaderSlice<&str, i32>> = header_vec!["foo"; 1, 2].into_box().into();
+    assert_eq!(*arc, *header_vec!["foo"; 1, 2]);
+}
+
+#[test]
+fn new_arc() {
+    let arc = HeaderSlice::new_arc(7u64, [1u8, 2, 3]);
+    let other = arc.clone();
+    let handle = std::thread::spawn(move || other.body.iter().map(|&x| x as u64).sum::<u64>());
+    assert_eq!(handle.join().unwrap(), 6);
+    assert_eq!(arc.head, 7);
+}
+
+#[test]
+fn try_from_rc() {
+    let rc = HeaderSlice::new_rc("foo", [1, 2, 3]);
+    let other = rc.clone();
+    let rc = HeaderVec::try_from_rc(rc).unwrap_err();
+    drop(other);
+    let weak = Rc::downgrade(&rc);
+    let rc = HeaderVec::try_from_rc(rc).unwrap_err();
+    drop(weak);
+    let v = HeaderVec::try_from_rc(rc).unwrap();
+    assert_eq!(v, header_vec!["foo"; 1, 2, 3]);
+}
+
+#[test]
+fn try_from_arc_drops_once() {
+    let counter = Rc::new(());
+    let arc = HeaderSlice::new_arc(counter.clone(), [counter.clone(), counter.clone()]);
+    assert_eq!(Rc::strong_count(&counter), 4);
+    let mut v = HeaderVec::try_from_arc(arc).unwrap();
+    assert_eq!(Rc::strong_count(&counter), 4);
+    v.push(counter.clone());
+    drop(v);
+    assert_eq!(Rc::strong_count(&counter), 1);
+}
+
+#[test]
+fn try_from_rc_zst() {
+    let rc = HeaderSlice::new_rc((), [(), ()]);
+    let v = HeaderVec::try_from_rc(rc).unwrap();
+    assert_eq!(v.len(), 2);
+}
@@ -0,0 +1,98 @@
+use crate::{header_vec, HeaderSlice, HeaderVec, SmallHeaderVec};
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+
+#[test]
+fn stays_inline() {
+    let mut v = SmallHeaderVec::<_, _, 4>::new("foo");
+    for i in 0..4 {
+        v.push(i);
+    }
+    assert!(!v.spilled());
+    assert_eq!(v.capacity(), 4);
+    assert_eq!(v, header_vec!["foo"; 0, 1, 2, 3]);
+    assert_eq!(v.pop(), Some(3));
+    assert_eq!(v.len(), 3);
+}
+
+#[test]
+fn spills() {
+    let mut v = SmallHeaderVec::<_, _, 2>::new("foo");
+    v.extend([1, 2, 3, 4, 5]);
+    assert!(v.spilled());
+    assert!(v.capacity() >= 5);
+    assert_eq!(v, header_vec!["foo"; 1, 2, 3, 4, 5]);
+    let slice: &HeaderSlice<&str, i32> = &v;
+    assert_eq!(slice.head, "foo");
+}
+
+#[test]
+fn with_capacity() {
+    let v = SmallHeaderVec::<u8, u32, 4>::with_capacity(0, 4);
+    assert!(!v.spilled());
+    let v = SmallHeaderVec::<u8, u32, 4>::with_capacity(0, 5);
+    assert!(v.spilled());
+}
+
+#[test]
+fn insert_remove() {
+    let mut v = SmallHeaderVec::<_, _, 3>::from_iter("foo", [1, 3]);
+    v.insert(1, 2);
+    assert!(!v.spilled());
+    v.insert(0, 0);
+    assert!(v.spilled());
+    assert_eq!(v, header_vec!["foo"; 0, 1, 2, 3]);
+    assert_eq!(v.remove(2), Some(2));
+    assert_eq!(v.remove(7), None);
+    assert_eq!(v, header_vec!["foo"; 0, 1, 3]);
+
+    let mut v = SmallHeaderVec::<_, _, 3>::from_iter("foo", [1, 2, 3]);
+    assert_eq!(v.remove(0), Some(1));
+    assert_eq!(v, header_vec!["foo"; 2, 3]);
+}
+
+#[test]
+fn shrink_to_fit_unspills() {
+    let mut v = SmallHeaderVec::<_, _, 4>::from_iter("foo", 0..10);
+    assert!(v.spilled());
+    v.truncate(3);
+    v.shrink_to_fit();
+    assert!(!v.spilled());
+    assert_eq!(v, header_vec!["foo"; 0, 1, 2]);
+}
+
+#[test]
+fn header_vec_round_trip() {
+    let v = SmallHeaderVec::<_, _, 4>::from_iter("foo", [1, 2]);
+    let hv: HeaderVec<&str, i32> = v.into();
+    assert_eq!(hv, header_vec!["foo"; 1, 2]);
+    let v = SmallHeaderVec::<_, _, 4>::from(hv);
+    assert!(v.spilled());
+    assert_eq!(v.into_box().body, [1, 2]);
+}
+
+#[test]
+fn drops() {
+    let rc = Rc::new(());
+    let mut v = SmallHeaderVec::<_, _, 2>::new(rc.clone());
+    v.push(rc.clone());
+    v.push(rc.clone());
+    v.truncate(1);
+    assert_eq!(Rc::strong_count(&rc), 3);
+    let c = v.clone();
+    v.push(rc.clone());
+    v.push(rc.clone());
+    assert!(v.spilled());
+    assert_eq!(Rc::strong_count(&rc), 7);
+    drop(v);
+    drop(c);
+    assert_eq!(Rc::strong_count(&rc), 1);
+}
+
+#[test]
+fn zst() {
+    let mut v = SmallHeaderVec::<(), (), 2>::new(());
+    v.extend([(); 5]);
+    assert_eq!(v.len(), 5);
+    assert_eq!(v.body.iter().collect::<Vec<_>>().len(), 5);
+}
@@ -0,0 +1,92 @@
+use crate::HeaderSoa;
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+
+#[test]
+fn push_pop() {
+    let mut soa = HeaderSoa::<_, (u8, u64, &str)>::with_capacity("foo", 0);
+    for i in 0..20u8 {
+        soa.push((i, i as u64 * 100, "x"));
+    }
+    assert!(soa.capacity() >= 20);
+    let (a, b, c) = soa.slices();
+    assert_eq!(a, (0..20).collect::<Vec<u8>>());
+    assert_eq!(b, (0..20).map(|i| i * 100).collect::<Vec<u64>>());
+    assert!(c.iter().all(|&s| s == "x"));
+    assert_eq!(soa.pop(), Some((19, 1900, "x")));
+    assert_eq!(soa.len(), 19);
+    assert_eq!(*soa.head(), "foo");
+}
+
+#[test]
+fn reserve_grows_geometrically() {
+    let mut soa = HeaderSoa::<_, (u8, u16)>::with_capacity("foo", 0);
+    soa.push((1, 2));
+    assert_eq!(soa.capacity(), crate::vec::MIN_CAP);
+    soa.reserve(crate::vec::MIN_CAP);
+    assert_eq!(soa.capacity(), crate::vec::MIN_CAP * 2);
+}
+
+#[test]
+#[should_panic(expected = "capacity overflow")]
+fn reserve_overflow_panics() {
+    let mut soa = HeaderSoa::<_, (u8, u16)>::with_capacity("foo", 0);
+    soa.push((1, 2));
+    soa.reserve(usize::MAX);
+}
+
+#[test]
+fn insert_remove() {
+    let mut soa = HeaderSoa::<(), (u16, char)>::from_iter((), [(1, 'a'), (3, 'c')]);
+    soa.insert(1, (2, 'b'));
+    soa.insert(0, (0, '_'));
+    assert_eq!(soa.slices(), (&[0, 1, 2, 3][..], &['_', 'a', 'b', 'c'][..]));
+    assert_eq!(soa.remove(1), Some((1, 'a')));
+    assert_eq!(soa.remove(3), None);
+    assert_eq!(soa.swap_remove(0), Some((0, '_')));
+    assert_eq!(soa.slices(), (&[3, 2][..], &['c', 'b'][..]));
+    let (nums, _) = soa.slices_mut();
+    nums[0] = 30;
+    assert_eq!(soa.slices().0, [30, 2]);
+}
+
+#[test]
+fn shrink_to_fit() {
+    let mut soa = HeaderSoa::<u32, (u8, u32)>::new(7);
+    soa.extend((0..5).map(|i| (i, i as u32)));
+    soa.shrink_to_fit();
+    assert_eq!(soa.capacity(), 5);
+    assert_eq!(soa.slices(), (&[0, 1, 2, 3, 4][..], &[0, 1, 2, 3, 4][..]));
+    soa.push((5, 5));
+    assert_eq!(
+        soa.slices(),
+        (&[0, 1, 2, 3, 4, 5][..], &[0, 1, 2, 3, 4, 5][..])
+    );
+}
+
+#[test]
+fn drops_values() {
+    let rc = Rc::new(());
+    let mut soa = HeaderSoa::<_, (Rc<()>, Rc<()>)>::new(rc.clone());
+    for _ in 0..3 {
+        soa.push((rc.clone(), rc.clone()));
+    }
+    let other = soa.clone();
+    assert_eq!(Rc::strong_count(&rc), 15);
+    soa.truncate(1);
+    assert_eq!(Rc::strong_count(&rc), 11);
+    drop(soa);
+    drop(other);
+    assert_eq!(Rc::strong_count(&rc), 1);
+}
+
+#[test]
+fn zero_sized_columns() {
+    let mut soa = HeaderSoa::<(), ((), u8)>::with_capacity((), 1);
+    soa.extend([((), 1), ((), 2), ((), 3)]);
+    assert_eq!(soa.slices(), (&[(), (), ()][..], &[1, 2, 3][..]));
+    assert_eq!(
+        alloc::format!("{:?}", soa),
+        "HeaderSoa { head: (), columns: ([(), (), ()], [1, 2, 3]) }"
+    );
+}
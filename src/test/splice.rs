@@ -0,0 +1,63 @@
+use crate::header_vec;
+use alloc::vec::Vec;
+
+#[test]
+fn splice_same_len() {
+    let mut v = header_vec!["foo"; 1, 2, 3, 4, 5];
+    let removed: Vec<_> = v.splice(1..3, [20, 30]).collect();
+    assert_eq!(removed, [2, 3]);
+    assert_eq!(v, header_vec!["foo"; 1, 20, 30, 4, 5]);
+}
+
+#[test]
+fn splice_longer() {
+    let mut v = header_vec!["foo"; 1, 2, 3];
+    let removed: Vec<_> = v
+        .splice(1..2, [10, 11, 12, 13, 14, 15, 16, 17, 18])
+        .collect();
+    assert_eq!(removed, [2]);
+    assert_eq!(
+        v,
+        header_vec!["foo"; 1, 10, 11, 12, 13, 14, 15, 16, 17, 18, 3]
+    );
+}
+
+#[test]
+fn splice_shorter() {
+    let mut v = header_vec!["foo"; 1, 2, 3, 4, 5];
+    v.splice(1..4, Some(9));
+    assert_eq!(v, header_vec!["foo"; 1, 9, 5]);
+}
+
+#[test]
+fn splice_at_end() {
+    let mut v = header_vec!["foo"; 1, 2, 3];
+    v.splice(2.., [7, 8, 9]);
+    assert_eq!(v, header_vec!["foo"; 1, 2, 7, 8, 9]);
+    v.splice(5.., []);
+    assert_eq!(v, header_vec!["foo"; 1, 2, 7, 8, 9]);
+}
+
+#[test]
+fn splice_inaccurate_size_hint() {
+    let mut v = header_vec!["foo"; 1, 2, 3];
+    // `filter` has a lower bound of 0, so the remaining values have to be collected first
+    v.splice(1..2, (10..20).filter(|x| x % 2 == 0));
+    assert_eq!(v, header_vec!["foo"; 1, 10, 12, 14, 16, 18, 3]);
+}
+
+#[test]
+fn splice_partially_consumed() {
+    let mut v = header_vec!["foo"; 1, 2, 3, 4, 5];
+    let mut s = v.splice(1..4, [6, 7]);
+    assert_eq!(s.next_back(), Some(4));
+    drop(s);
+    assert_eq!(v, header_vec!["foo"; 1, 6, 7, 5]);
+}
+
+#[test]
+fn splice_zst() {
+    let mut v = header_vec!["foo"; (); 3];
+    v.splice(1..2, [(), (), ()]);
+    assert_eq!(v.len(), 5);
+}
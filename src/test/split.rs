@@ -0,0 +1,124 @@
+use crate::{header_vec, HeaderVec};
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+
+#[test]
+fn split_off() {
+    let mut v = header_vec!["foo"; 1, 2, 3, 4, 5];
+    let tail = v.split_off(2, "bar");
+    assert_eq!(v, header_vec!["foo"; 1, 2]);
+    assert_eq!(tail, header_vec!["bar"; 3, 4, 5]);
+}
+
+#[test]
+fn split_off_ends() {
+    let mut v = header_vec!["foo"; 1, 2, 3];
+    let empty = v.split_off(3, "bar");
+    assert_eq!(empty, header_vec!["bar";]);
+    let all = v.split_off(0, "baz");
+    assert_eq!(v, header_vec!["foo";]);
+    assert_eq!(all, header_vec!["baz"; 1, 2, 3]);
+}
+
+#[test]
+#[should_panic]
+fn split_off_out_of_bounds() {
+    let mut v = header_vec!["foo"; 1, 2, 3];
+    v.split_off(4, "bar");
+}
+
+#[test]
+fn append() {
+    let mut v = header_vec!["foo"; 1, 2];
+    let mut other = header_vec![7u8; 3, 4, 5, 6, 7, 8, 9, 10, 11];
+    v.append(&mut other);
+    assert_eq!(v, header_vec!["foo"; 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11]);
+    assert_eq!(other, header_vec![7u8;]);
+}
+
+#[test]
+#[should_panic(expected = "capacity overflow")]
+fn append_overflow_panics() {
+    // a zero-sized body always has a capacity of `usize::MAX`, so the vector can be full
+    let (ptr, _, cap) = header_vec![(); ()].into_raw_parts();
+    assert_eq!(cap, usize::MAX);
+    let mut v = unsafe { HeaderVec::from_raw_parts(ptr, usize::MAX, cap) };
+    v.append(&mut header_vec![(); ()]);
+}
+
+#[test]
+fn append_owned() {
+    let rc = Rc::new(());
+    let mut v = header_vec!["foo"; rc.clone()];
+    let other = header_vec![rc.clone(); rc.clone(), rc.clone()];
+    let head = v.append_owned(other);
+    assert_eq!(v.len(), 3);
+    assert_eq!(Rc::strong_count(&rc), 5);
+    drop(head);
+    drop(v);
+    assert_eq!(Rc::strong_count(&rc), 1);
+}
+
+#[test]
+fn extract_if() {
+    let mut v = header_vec!["foo"; 1, 2, 3, 4, 5, 6, 7, 8];
+    let evens: Vec<_> = v.extract_if(.., |x| *x % 2 == 0).collect();
+    assert_eq!(evens, [2, 4, 6, 8]);
+    assert_eq!(v, header_vec!["foo"; 1, 3, 5, 7]);
+}
+
+#[test]
+fn extract_if_range() {
+    let mut v = header_vec!["foo"; 1, 2, 3, 4, 5, 6, 7, 8];
+    let evens: Vec<_> = v.extract_if(2..6, |x| *x % 2 == 0).collect();
+    assert_eq!(evens, [4, 6]);
+    assert_eq!(v, header_vec!["foo"; 1, 2, 3, 5, 7, 8]);
+}
+
+#[test]
+fn extract_if_lazy() {
+    let mut v = header_vec!["foo"; 1, 2, 3, 4, 5, 6];
+    let mut visited = 0;
+    let mut iter = v.extract_if(.., |x| {
+        visited += 1;
+        *x % 2 == 0
+    });
+    assert_eq!(iter.next(), Some(2));
+    drop(iter);
+    assert_eq!(visited, 2);
+    assert_eq!(v, header_vec!["foo"; 1, 3, 4, 5, 6]);
+}
+
+#[test]
+fn extract_if_debug() {
+    let mut v = header_vec!["foo"; 1, 2, 3, 4, 5, 6];
+    let mut iter = v.extract_if(1..4, |x| *x % 2 == 0);
+    assert_eq!(alloc::format!("{:?}", iter), "ExtractIf([2, 3, 4])");
+    assert_eq!(iter.next(), Some(2));
+    assert_eq!(alloc::format!("{:?}", iter), "ExtractIf([3, 4])");
+}
+
+#[test]
+fn extract_if_leak() {
+    let mut v: HeaderVec<&str, i32> = header_vec!["foo"; 1, 2, 3];
+    core::mem::forget(v.extract_if(.., |_| true));
+    assert_eq!(v.len(), 0);
+}
+
+#[test]
+fn extract_if_panic() {
+    let rc = Rc::new(());
+    let mut v = header_vec![(); (0, rc.clone()), (1, rc.clone()), (2, rc.clone()), (3, rc.clone())];
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let iter = v.extract_if(.., |x| {
+            if x.0 == 2 {
+                panic!("extract_if panicked");
+            }
+            x.0 == 1
+        });
+        iter.for_each(drop);
+    }));
+    assert!(result.is_err());
+    assert_eq!(v.body.iter().map(|x| x.0).collect::<Vec<_>>(), [0, 2, 3]);
+    assert_eq!(Rc::strong_count(&rc), 4);
+}
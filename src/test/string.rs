@@ -0,0 +1,109 @@
+use crate::{header_string, header_vec, HeaderStr, HeaderString};
+use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
+use core::fmt::Write;
+
+#[test]
+fn push() {
+    let mut s = HeaderString::new("foo");
+    s.push_str("abc");
+    s.push('é');
+    s.push('d');
+    assert_eq!(&s.body, "abcéd");
+    assert_eq!(s.len(), 6);
+    assert_eq!(s, header_string!["foo"; "abcéd"]);
+}
+
+#[test]
+fn pop() {
+    let mut s = header_string!["foo"; "aé"];
+    assert_eq!(s.pop(), Some('é'));
+    assert_eq!(s.pop(), Some('a'));
+    assert_eq!(s.pop(), None);
+    assert_eq!(s, header_string!["foo";]);
+}
+
+#[test]
+fn insert() {
+    let mut s = header_string!["foo"; "aéb"];
+    s.insert(3, 'ü');
+    assert_eq!(&s.body, "aéüb");
+    s.insert_str(0, "xyz");
+    assert_eq!(&s.body, "xyzaéüb");
+}
+
+#[test]
+#[should_panic]
+fn insert_not_char_boundary() {
+    let mut s = header_string!["foo"; "aéb"];
+    s.insert(2, 'x');
+}
+
+#[test]
+fn remove() {
+    let mut s = header_string!["foo"; "aéb"];
+    assert_eq!(s.remove(1), 'é');
+    assert_eq!(&s.body, "ab");
+    assert_eq!(s.remove(1), 'b');
+    assert_eq!(&s.body, "a");
+}
+
+#[test]
+#[should_panic]
+fn remove_not_char_boundary() {
+    let mut s = header_string!["foo"; "aéb"];
+    s.remove(2);
+}
+
+#[test]
+fn truncate() {
+    let mut s = header_string!["foo"; "aéb"];
+    s.truncate(3);
+    assert_eq!(&s.body, "aé");
+}
+
+#[test]
+#[should_panic]
+fn truncate_not_char_boundary() {
+    let mut s = header_string!["foo"; "aéb"];
+    s.truncate(2);
+}
+
+#[test]
+fn write() {
+    let mut s = header_string![1; "{} + {}", 2, 3];
+    write!(s, " = {}", 5).unwrap();
+    assert_eq!(s, header_string![1; "2 + 3 = 5"]);
+}
+
+#[test]
+fn deref_str() {
+    let s = header_string!["foo"; "Hello"];
+    assert!(s.starts_with("He"));
+    assert_eq!(s.to_uppercase(), "HELLO");
+}
+
+#[test]
+fn from_utf8() {
+    let s = HeaderString::from_utf8(header_vec!["foo"; b'h', b'i']).unwrap();
+    assert_eq!(s, header_string!["foo"; "hi"]);
+    let err = HeaderString::from_utf8(header_vec!["foo"; b'h', 0xff]).unwrap_err();
+    assert_eq!(err.utf8_error().valid_up_to(), 1);
+    assert_eq!(err.into_header_vec(), header_vec!["foo"; b'h', 0xff]);
+}
+
+#[test]
+fn header_str() {
+    let v = header_vec!["foo"; b'h', b'i'];
+    let s = HeaderStr::from_header_slice(&v).unwrap();
+    assert_eq!(&s.body, "hi");
+    assert_eq!(s.as_header_slice(), &v);
+    assert_eq!(s.to_owned(), header_string!["foo"; "hi"]);
+}
+
+#[test]
+fn into_box() {
+    let b: Box<HeaderStr<_>> = header_string!["foo"; "hello"].into();
+    assert_eq!(&b.body, "hello");
+    assert_eq!(HeaderString::from_box(b), header_string!["foo"; "hello"]);
+}
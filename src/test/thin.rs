@@ -0,0 +1,119 @@
+use crate::{header_vec, HeaderSlice, HeaderVec, ThinHeaderVec};
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::mem;
+
+#[test]
+fn one_pointer_wide() {
+    assert_eq!(
+        mem::size_of::<ThinHeaderVec<&str, i32>>(),
+        mem::size_of::<usize>()
+    );
+    assert_eq!(
+        mem::size_of::<Option<ThinHeaderVec<&str, i32>>>(),
+        mem::size_of::<usize>()
+    );
+}
+
+#[test]
+fn push_pop() {
+    let mut v = ThinHeaderVec::new("foo");
+    for i in 0..128 {
+        v.push(i);
+    }
+    assert_eq!(v, HeaderVec::from_iter("foo", 0..128));
+    for i in (0..128).rev() {
+        assert_eq!(v.pop(), Some(i));
+    }
+    assert_eq!(v.pop(), None);
+    assert_eq!(v, header_vec!["foo";]);
+}
+
+#[test]
+fn insert_remove() {
+    let mut v = ThinHeaderVec::copy_from_slice("foo", &[1, 2, 3, 4, 5, 6]);
+    v.insert(2, 123);
+    assert_eq!(v, header_vec!["foo"; 1, 2, 123, 3, 4, 5, 6]);
+    v.insert(7, 7);
+    assert_eq!(v, header_vec!["foo"; 1, 2, 123, 3, 4, 5, 6, 7]);
+    assert_eq!(v.remove(3), Some(3));
+    assert_eq!(v.remove(10), None);
+    assert_eq!(v.swap_remove(0), Some(1));
+    assert_eq!(v, header_vec!["foo"; 7, 2, 123, 4, 5, 6]);
+}
+
+#[test]
+fn truncate_and_resize() {
+    let mut v = ThinHeaderVec::from_iter("foo", 1..=5);
+    v.truncate(2);
+    assert_eq!(v, header_vec!["foo"; 1, 2]);
+    v.resize(4, 9);
+    assert_eq!(v, header_vec!["foo"; 1, 2, 9, 9]);
+    v.resize_default(5);
+    assert_eq!(v, header_vec!["foo"; 1, 2, 9, 9, 0]);
+}
+
+#[test]
+fn reserve_and_shrink() {
+    let mut v = ThinHeaderVec::copy_from_slice("foo", &[1, 2, 3, 4]);
+    v.reserve_exact(6);
+    assert_eq!(v.capacity(), 10);
+    v.shrink_to_fit();
+    assert_eq!(v.capacity(), 4);
+    assert_eq!(v, header_vec!["foo"; 1, 2, 3, 4]);
+}
+
+#[test]
+#[should_panic(expected = "capacity overflow")]
+fn reserve_overflow_panics() {
+    let mut v = ThinHeaderVec::copy_from_slice("foo", &[1, 2, 3]);
+    v.reserve(usize::MAX);
+}
+
+#[test]
+fn from_header_vec() {
+    let v = ThinHeaderVec::from(header_vec!["foo"; 1, 2, 3]);
+    assert_eq!(v, header_vec!["foo"; 1, 2, 3]);
+    // alignment of the header slice is smaller than the prefix
+    let v = ThinHeaderVec::from(header_vec![1u8; 2u8, 3, 4]);
+    assert_eq!(v, header_vec![1u8; 2u8, 3, 4]);
+    // alignment of the header slice is larger than the prefix
+    let v = ThinHeaderVec::from(header_vec![1u128; 2u8, 3, 4]);
+    assert_eq!(v, header_vec![1u128; 2u8, 3, 4]);
+}
+
+#[test]
+fn into_header_vec() {
+    let mut v = ThinHeaderVec::new([1u8, 2, 3]);
+    v.extend(0u16..20);
+    let v: HeaderVec<_, _> = v.into();
+    assert_eq!(v, HeaderVec::from_iter([1u8, 2, 3], 0u16..20));
+}
+
+#[test]
+fn into_box() {
+    let b: Box<HeaderSlice<_, _>> = ThinHeaderVec::from_iter("foo", 0..5).into();
+    assert_eq!(*b, header_vec!["foo"; 0, 1, 2, 3, 4]);
+    let v = ThinHeaderVec::from_box(b);
+    assert_eq!(v, header_vec!["foo"; 0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn into_header_values() {
+    let v = ThinHeaderVec::from_iter("foo", 1..=4);
+    let (head, vals) = v.into_header_values();
+    assert_eq!(head, "foo");
+    assert_eq!(&vals.collect::<Vec<_>>(), &[1, 2, 3, 4]);
+}
+
+#[test]
+fn zst() {
+    let mut v = ThinHeaderVec::new(());
+    for _ in 0..20 {
+        v.push(());
+    }
+    assert_eq!(v.capacity(), usize::MAX);
+    assert_eq!(v, header_vec![(); (); 20]);
+    let v = HeaderVec::from(v);
+    assert_eq!(v, header_vec![(); (); 20]);
+}
@@ -0,0 +1,129 @@
+use crate::{header_vec, HeaderSlice, HeaderSliceMut, HeaderSliceRef, HeaderVec};
+use alloc::format;
+use alloc::vec::Vec;
+
+/// Accepts a full header slice or a view of part of one.
+fn total<'a>(src: impl Into<HeaderSliceRef<'a, i32, i32>>) -> i32 {
+    let src = src.into();
+    src.iter().map(|x| x * src.head).sum()
+}
+
+#[test]
+fn conversions() {
+    let mut v = header_vec![2; 1, 2, 3, 4];
+    assert_eq!(total(&v), 20);
+    let s: &HeaderSlice<i32, i32> = &v;
+    assert_eq!(total(s), 20);
+    assert_eq!(total(v.as_view().get(1..3).unwrap()), 10);
+
+    let view = v.as_view();
+    assert!(core::ptr::eq(view.head, &v.head));
+    assert_eq!(view.body.as_ptr(), v.body.as_ptr());
+
+    let view: HeaderSliceMut<_, _> = (&mut v).into();
+    assert_eq!(total(view), 20);
+    let view: HeaderSliceMut<_, _> = (&mut *v).into();
+    assert_eq!(view.len(), 4);
+}
+
+#[test]
+fn get() {
+    let v = header_vec!["foo"; 1, 2, 3, 4];
+    let view = v.as_view();
+    assert_eq!(view.get(1..3).unwrap().body, [2, 3]);
+    assert_eq!(view.get(..=1).unwrap().body, [1, 2]);
+    assert_eq!(view.get(4..).unwrap().body, []);
+    assert_eq!(*view.get(..).unwrap().head, "foo");
+    assert!(view.get(3..5).is_none());
+    let (start, end) = (3, 2);
+    assert!(view.get(start..end).is_none());
+}
+
+#[test]
+fn split_at() {
+    let v = header_vec!["foo"; 1, 2, 3, 4];
+    let (front, back) = v.as_view().split_at(1);
+    assert_eq!((*front.head, front.body), ("foo", &[1][..]));
+    assert_eq!((*back.head, back.body), ("foo", &[2, 3, 4][..]));
+    let (front, back) = back.split_at(3);
+    assert_eq!(front.len(), 3);
+    assert!(back.is_empty());
+}
+
+#[test]
+#[should_panic]
+fn split_at_out_of_bounds() {
+    let v = header_vec!["foo"; 1, 2, 3];
+    v.as_view().split_at(4);
+}
+
+#[test]
+fn chunks_and_windows() {
+    let v = header_vec!["foo"; 1, 2, 3, 4, 5];
+    let chunks: Vec<_> = v.as_view().chunks(2).collect();
+    assert_eq!(chunks.len(), 3);
+    assert!(chunks.iter().all(|x| *x.head == "foo"));
+    assert_eq!(chunks[2].body, [5]);
+    assert_eq!(v.as_view().chunks(2).next_back().unwrap().body, [5]);
+    assert_eq!(v.as_view().chunks(2).nth(1).unwrap().body, [3, 4]);
+
+    let windows = v.as_view().windows(3);
+    assert_eq!(windows.len(), 3);
+    let sums: Vec<i32> = windows.map(|x| x.iter().sum()).collect();
+    assert_eq!(sums, [6, 9, 12]);
+    assert_eq!(v.as_view().windows(6).count(), 0);
+}
+
+#[test]
+fn mutable_view() {
+    let mut v = header_vec![0; 1, 2, 3, 4];
+    let mut view = v.as_view_mut();
+    *view.head = 10;
+    for x in view.iter_mut() {
+        *x *= 2;
+    }
+    assert_eq!(view.get(1..2).unwrap().body, [4]);
+    assert_eq!(view.split_at(2).1.body, [6, 8]);
+    assert_eq!(view.chunks(3).count(), 2);
+    assert_eq!(view.windows(4).count(), 1);
+    assert_eq!(view.as_view().body, [2, 4, 6, 8]);
+    {
+        let sub = view.reborrow().get_mut(2..).unwrap();
+        *sub.head += 1;
+        sub.body[0] = 0;
+    }
+    let (front, back) = view.split_at_mut(1);
+    *front.head += 1;
+    front.body[0] = -1;
+    back[0] = -2;
+    assert_eq!(v, header_vec![12; -1, -2, 0, 8]);
+
+    let mut view = v.as_view_mut();
+    assert!(view.reborrow().get_mut(5..).is_none());
+    for x in view {
+        *x = 1;
+    }
+    assert_eq!(v.body, [1; 4]);
+}
+
+#[test]
+fn traits() {
+    let v = header_vec!["foo"; 1, 2, 1, 2];
+    let (a, b) = v.as_view().split_at(2);
+    assert_eq!(a, b);
+    assert!(a.get(..1).unwrap() < b);
+    assert_eq!(a.to_header_vec(), header_vec!["foo"; 1, 2]);
+    assert_eq!(format!("{:?}", a), "[\"foo\"; 1, 2]");
+    assert_eq!(format!("{:?}", a), format!("{:?}", a.to_header_vec()));
+    assert_eq!(format!("{:?}", a.get(..0).unwrap()), "[\"foo\";]");
+
+    let hash = |x: &dyn Fn(&mut std::collections::hash_map::DefaultHasher)| {
+        use core::hash::Hasher;
+        let mut state = std::collections::hash_map::DefaultHasher::new();
+        x(&mut state);
+        state.finish()
+    };
+    use core::hash::Hash;
+    let whole = HeaderVec::from_iter("foo", [1, 2]);
+    assert_eq!(hash(&|s| a.hash(s)), hash(&|s| whole.hash(s)));
+}
@@ -0,0 +1,244 @@
+//! Zero-sized headers and elements, and vectors whose allocation has a zero-size layout.
+//! These only use safe, deterministic operations so they can be run under Miri.
+
+use crate::{HeaderSlice, HeaderVec, ThinHeaderVec};
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::cell::Cell;
+use core::fmt::Debug;
+use core::mem::MaybeUninit;
+
+/// A zero-sized element with a meaningful order.
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Debug)]
+struct Unit;
+
+std::thread_local! {
+    static DROPS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// A zero-sized value that counts how many times it's dropped on the current thread.
+#[derive(Clone, Default, PartialEq, Debug)]
+struct Tracked;
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        DROPS.with(|d| d.set(d.get() + 1));
+    }
+}
+
+fn drops() -> usize {
+    DROPS.with(|d| d.get())
+}
+
+/// Calls every public method of `HeaderVec` that applies to any header and element type.
+fn exercise<H, T>(head: H, val: T)
+where
+    H: Clone + PartialEq + Debug,
+    T: Clone + Default + Ord + Debug,
+{
+    let empty = HeaderVec::<H, T>::with_capacity(head.clone(), 0);
+    assert!(empty.is_empty());
+    assert_eq!(empty.clone(), empty);
+    assert!(HeaderVec::<H, T>::try_with_capacity(head.clone(), 0).is_ok());
+    let _ = empty.as_ptr();
+    drop(empty);
+
+    let mut v = HeaderVec::new(head.clone());
+    v.push(val.clone());
+    v.try_push(val.clone()).unwrap();
+    v.insert(0, val.clone());
+    v.try_insert(3, val.clone()).unwrap();
+    assert_eq!(v.len(), 4);
+    assert!(v.capacity() >= 4);
+    assert_eq!(v.pop(), Some(val.clone()));
+    assert_eq!(v.remove(0), Some(val.clone()));
+    assert_eq!(v.remove(5), None);
+    assert_eq!(v.swap_remove(0), Some(val.clone()));
+    assert_eq!(v.swap_remove(1), None);
+    v.truncate(0);
+    assert!(v.is_empty());
+    assert_eq!(v.pop(), None);
+
+    v.reserve(10);
+    v.reserve_exact(10);
+    v.try_reserve(10).unwrap();
+    v.try_reserve_exact(10).unwrap();
+    assert!(v.capacity() >= 10);
+    v.shrink_to_fit();
+    assert_eq!(
+        v.capacity(),
+        HeaderVec::<H, T>::with_capacity(head.clone(), 0).capacity()
+    );
+
+    v.resize(3, val.clone());
+    v.resize_with(5, T::default);
+    v.resize_default(6);
+    v.extend([val.clone(), val.clone()]);
+    v.try_extend([val.clone()]).unwrap();
+    assert_eq!(v.len(), 9);
+    v.clear_in_place();
+    v.clear();
+    assert!(v.is_empty());
+    v.shrink_to_fit();
+
+    let mut v = HeaderVec::from_iter(head.clone(), core::iter::repeat(val.clone()).take(6));
+    let w =
+        HeaderVec::try_from_iter(head.clone(), core::iter::repeat(val.clone()).take(6)).unwrap();
+    assert_eq!(v, w);
+    assert_eq!(v.drain(1..3).count(), 2);
+    assert_eq!(v.drain(..0).count(), 0);
+    assert_eq!(v.splice(0..1, [val.clone(), val.clone()]).count(), 1);
+    assert_eq!(v.len(), 5);
+    v.retain(|_| true);
+    v.retain_mut(|_| false);
+    assert!(v.is_empty());
+    v.extend(core::iter::repeat(val.clone()).take(4));
+    v.dedup();
+    v.dedup_by(|_, _| true);
+    v.dedup_by_key(|x| x.clone());
+    assert_eq!(v.len(), 1);
+    v.insert_sorted(val.clone());
+    assert_eq!(v.insert_or_replace_sorted(val.clone()), Some(val.clone()));
+    assert_eq!(v.len(), 2);
+    assert_eq!(v.extract_if(.., |_| true).count(), 2);
+    assert!(v.is_empty());
+
+    let mut v = HeaderVec::from_iter(head.clone(), core::iter::repeat(val.clone()).take(4));
+    let mut tail = v.split_off(1, head.clone());
+    assert_eq!((v.len(), tail.len()), (1, 3));
+    v.append(&mut tail);
+    assert!(tail.is_empty());
+    assert_eq!(v.append_owned(tail), head);
+    let empty = HeaderVec::<H, T>::with_capacity(head.clone(), 0);
+    v.append_owned(empty);
+    assert_eq!(v.len(), 4);
+
+    let boxed = v.clone().into_box();
+    assert_eq!(boxed.len(), 4);
+    let v2 = HeaderVec::from_box(boxed);
+    assert_eq!(v2, v);
+    let boxed = HeaderVec::<H, T>::with_capacity(head.clone(), 0).into_box();
+    assert!(boxed.is_empty());
+    assert_eq!(boxed.clone(), boxed);
+    assert!(HeaderVec::from_box(boxed).is_empty());
+
+    let rc: Rc<HeaderSlice<H, T>> = v.clone().into();
+    assert_eq!(HeaderVec::try_from_rc(rc).unwrap(), v);
+    let arc: Arc<HeaderSlice<H, T>> = HeaderVec::with_capacity(head.clone(), 0).into();
+    assert!(HeaderVec::try_from_arc(arc).unwrap().is_empty());
+
+    let (ptr, len, cap) = v.clone().into_raw_parts();
+    let v2 = unsafe { HeaderVec::from_raw_parts(ptr, len, cap) };
+    assert_eq!(v2, v);
+    let mut v3 = v2.clone();
+    let (_, len, cap) = v3.as_raw_parts();
+    assert_eq!((len, cap), (v3.len(), v3.capacity()));
+
+    assert_eq!(v.clone().into_values().count(), 4);
+    let (h, values) = v.clone().into_header_values();
+    assert_eq!(h, head);
+    assert_eq!(values.rev().count(), 4);
+    let mut iter = v.clone().into_iter();
+    assert_eq!(iter.head(), Some(&head));
+    iter.next();
+    assert_eq!(iter.as_slice().len(), 3);
+    assert_eq!(iter.as_mut_slice().len(), 3);
+    let v2 = iter.into_header_vec(head.clone());
+    assert_eq!(v2.len(), 3);
+    let mut iter = v.into_iter();
+    assert_eq!(iter.take_head(), Some(head.clone()));
+    assert_eq!(iter.take_head(), None);
+    assert_eq!(iter.count(), 4);
+
+    let uninit = HeaderVec::<H, MaybeUninit<T>>::new_uninit_values(head.clone(), 0);
+    assert!(unsafe { uninit.assume_init_values() }.is_empty());
+    let uninit = HeaderVec::<MaybeUninit<H>, T>::with_capacity(MaybeUninit::new(head.clone()), 0);
+    assert_eq!(unsafe { uninit.assume_init_head() }.head, head);
+
+    let mut thin = ThinHeaderVec::with_capacity(head.clone(), 0);
+    thin.push(val.clone());
+    thin.insert(0, val.clone());
+    thin.resize(4, val.clone());
+    thin.shrink_to_fit();
+    assert_eq!(thin.pop(), Some(val.clone()));
+    assert_eq!(thin.remove(0), Some(val.clone()));
+    thin.clear();
+    assert!(thin.into_header_vec().is_empty());
+}
+
+#[test]
+fn zst_head_and_body() {
+    exercise((), Unit);
+}
+
+#[test]
+fn zst_head() {
+    exercise((), 7u32);
+}
+
+#[test]
+fn zst_body() {
+    exercise(0x1234_5678_9abc_u64, Unit);
+}
+
+#[test]
+fn over_aligned_zst() {
+    #[repr(align(64))]
+    #[derive(Clone, PartialEq, Debug)]
+    struct Aligned;
+
+    let mut v = HeaderVec::<Aligned, Unit>::with_capacity(Aligned, 0);
+    assert_eq!(v.as_ptr().as_ptr() as *mut u8 as usize % 64, 0);
+    v.push(Unit);
+    assert_eq!(v.into_box().len(), 1);
+    exercise(Aligned, Unit);
+    exercise(Aligned, 1u8);
+}
+
+#[test]
+fn zst_capacity_is_consistent() {
+    let mut v = HeaderVec::<u8, Unit>::with_capacity(0, 3);
+    assert_eq!(v.capacity(), usize::MAX);
+    for _ in 0..100 {
+        v.push(Unit);
+    }
+    assert_eq!(v.capacity(), usize::MAX);
+    v.shrink_to_fit();
+    assert_eq!(v.capacity(), usize::MAX);
+    let (ptr, len, cap) = v.into_raw_parts();
+    assert_eq!((len, cap), (100, usize::MAX));
+    drop(unsafe { HeaderVec::from_raw_parts(ptr, len, cap) });
+    assert!(HeaderVec::<u8, Unit>::new(0)
+        .try_reserve(usize::MAX)
+        .is_ok());
+}
+
+#[test]
+fn zst_drops() {
+    let before = drops();
+    let mut v = HeaderVec::<Tracked, Tracked>::new(Tracked);
+    v.resize(5, Tracked);
+    assert_eq!(drops() - before, 0);
+    v.truncate(3);
+    assert_eq!(drops() - before, 2);
+    let mut values = v.into_iter();
+    values.next();
+    assert_eq!(drops() - before, 3);
+    drop(values);
+    // the last 2 elements and the header
+    assert_eq!(drops() - before, 6);
+}
+
+#[test]
+fn empty_vec_conversions() {
+    let v: Vec<u32> = HeaderVec::<(), u32>::with_capacity((), 0).into();
+    assert!(v.is_empty());
+    let hv = HeaderVec::from(Vec::<u32>::new());
+    assert!(hv.is_empty());
+    let hv = HeaderVec::from(alloc::vec![Unit; 3]);
+    assert_eq!(Vec::from(hv), [Unit; 3]);
+    let empty: Box<HeaderSlice<(), u32>> = Box::<[u32]>::default().into();
+    assert!(HeaderVec::from_box(empty).is_empty());
+}
@@ -0,0 +1,596 @@
+use crate::allocator::{self, Global};
+use crate::error::{handle_reserve, TryReserveError};
+use crate::growth::{DefaultGrowth, GrowthPolicy};
+use crate::pair::Pair;
+use crate::slice::HeaderSlice;
+use crate::utils;
+use crate::vec::{HeaderVec, IntoValuesIter, MIN_CAP};
+use alloc::alloc::Layout;
+use alloc::borrow::{Borrow, BorrowMut};
+use alloc::boxed::Box;
+use core::cmp::Ordering;
+use core::fmt::{self, Debug};
+use core::hash::{self, Hash};
+use core::iter;
+use core::mem::{self, MaybeUninit};
+use core::ops::{Add, AddAssign};
+use core::ops::{Deref, DerefMut};
+use core::ptr::{self, NonNull};
+
+/// The length and capacity of a `ThinHeaderVec`, stored in front of the header.
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct Prefix {
+    len: usize,
+    cap: usize,
+}
+
+type ThinPair<H, T> = Pair<Prefix, Pair<H, MaybeUninit<T>>>;
+
+/// A vector with a header, like `HeaderVec`, whose length and capacity are stored in the same
+/// allocation as the header so the vector itself is a single pointer wide.
+pub struct ThinHeaderVec<H, T> {
+    ptr: NonNull<ThinPair<H, T>>,
+}
+
+impl<H, T> ThinHeaderVec<H, T> {
+    fn prefix(&self) -> &Prefix {
+        unsafe { &(*self.ptr.as_ptr()).0 }
+    }
+
+    fn prefix_mut(&mut self) -> &mut Prefix {
+        unsafe { &mut (*self.ptr.as_ptr()).0 }
+    }
+
+    /// Returns a pointer to the header and first element, just past the prefix.
+    fn pair_ptr(&self) -> NonNull<Pair<H, MaybeUninit<T>>> {
+        let ptr = unsafe { ptr::addr_of_mut!((*self.ptr.as_ptr()).1) };
+        unsafe { NonNull::new_unchecked(ptr) }
+    }
+
+    /// The total reserved capacity of the vector.
+    pub fn capacity(&self) -> usize {
+        if mem::size_of::<T>() == 0 {
+            usize::MAX
+        } else {
+            self.prefix().cap
+        }
+    }
+
+    /// Returns a pointer to a `HeaderSlice` representing this vector.
+    pub fn as_ptr(&self) -> NonNull<HeaderSlice<H, T>> {
+        crate::pair::pair_as_slice_ptr(self.pair_ptr().cast::<Pair<H, T>>(), self.prefix().len)
+    }
+
+    /// Convert `ptr` to a mutable reference to a HeaderSlice with the entire capacity of the vector.
+    fn inner_mut(&mut self) -> &mut HeaderSlice<H, MaybeUninit<T>> {
+        let ptr = crate::pair::pair_as_slice_ptr(self.pair_ptr(), self.capacity());
+        unsafe { &mut *ptr.as_ptr() }
+    }
+
+    /// Returns the `Layout` to be used when allocating the specified capacity.
+    fn get_layout(cap: usize) -> Layout {
+        HeaderSlice::<H, T>::prefixed_layout_for_len::<Prefix>(cap).0
+    }
+
+    /// Returns the offset of the header from the start of the allocation.
+    fn head_offset() -> usize {
+        HeaderSlice::<H, T>::prefixed_layout_for_len::<Prefix>(0).1
+    }
+
+    /// Reallocate so that the vector has the exact requested capacity
+    /// unsafe because the new capacity may be less than self.len
+    unsafe fn realloc_exact(&mut self, count: usize) {
+        if mem::size_of::<T>() == 0 {
+            return;
+        }
+        if count == self.prefix().cap {
+            return;
+        }
+        let old_layout = Self::get_layout(self.prefix().cap);
+        let new_layout = Self::get_layout(count);
+        let bytes_ptr = allocator::reallocate(&Global, self.ptr.cast(), old_layout, new_layout);
+        self.ptr = bytes_ptr.cast();
+        self.prefix_mut().cap = count;
+    }
+
+    /// Increase capacity as chosen by `DefaultGrowth`, if `target_len` elements don't fit.
+    fn grow(&mut self, target_len: usize) {
+        let cap = self.capacity();
+        if target_len > cap {
+            let target_cap = DefaultGrowth::grow(cap, target_len);
+            unsafe { self.realloc_exact(target_cap) }
+        }
+    }
+
+    /// Decrease capacity if `DefaultGrowth` decides to now that only `target_len` elements are in
+    /// use.
+    /// unsafe because the new capacity may be less than self.len
+    unsafe fn shrink(&mut self, target_len: usize) {
+        if let Some(target_cap) = DefaultGrowth::shrink(self.capacity(), target_len) {
+            self.realloc_exact(target_cap.max(target_len));
+        }
+    }
+
+    /// Reallocates if necessary to hold a vector of the given length
+    /// unsafe because the new capacity may be less than self.len
+    unsafe fn realloc_for(&mut self, len: usize) {
+        if len < self.prefix().len {
+            self.shrink(len);
+        } else if len > self.capacity() {
+            self.grow(len);
+        }
+    }
+
+    /// Returns the length after adding `additional` elements, panicking if it overflows.
+    fn len_after(&self, additional: usize) -> usize {
+        let new_len = self.prefix().len.checked_add(additional);
+        handle_reserve(new_len.ok_or(TryReserveError::CapacityOverflow))
+    }
+
+    /// Push a value to the end of the vector.
+    pub fn push(&mut self, val: T) {
+        let new_len = self.len_after(1);
+        if new_len > self.capacity() {
+            self.grow(new_len);
+        }
+        let index = self.prefix().len;
+        self.inner_mut().body[index] = MaybeUninit::new(val);
+        self.prefix_mut().len = new_len;
+    }
+
+    /// Pop a value from the end of the vec, if there is one.
+    pub fn pop(&mut self) -> Option<T> {
+        if self.prefix().len == 0 {
+            return None;
+        }
+        let new_len = self.prefix().len - 1;
+        let val = unsafe { ptr::read(self.inner_mut().body[new_len].as_ptr()) };
+        unsafe { self.shrink(new_len) };
+        self.prefix_mut().len = new_len;
+        Some(val)
+    }
+
+    /// Removes a value at the given index, if it exists.
+    /// All entries after `index` will be shifted to the left.
+    pub fn remove(&mut self, index: usize) -> Option<T> {
+        let len = self.prefix().len;
+        if index >= len {
+            return None;
+        }
+        let target_ptr = unsafe { self.inner_mut().body.as_mut_ptr().add(index) };
+        let val = unsafe { ptr::read(target_ptr) };
+        let copy_len = len - index - 1;
+        let copy_src = unsafe { target_ptr.add(1) };
+        unsafe { ptr::copy(copy_src, target_ptr, copy_len) };
+        unsafe { self.shrink(len - 1) };
+        self.prefix_mut().len = len - 1;
+        Some(unsafe { val.assume_init() })
+    }
+
+    /// Remove an element at `index` if it exists by replacing it with the last
+    /// element of the vector.
+    pub fn swap_remove(&mut self, index: usize) -> Option<T> {
+        if index >= self.prefix().len {
+            return None;
+        }
+
+        // pop can't fail -- since index is in [0, len), len must be at least one
+        let last = self.pop().unwrap();
+
+        if index == self.prefix().len {
+            return Some(last);
+        }
+
+        Some(mem::replace(&mut self.body[index], last))
+    }
+
+    /// Inserts an element at `index`, shifting all elements after `index` to
+    /// the right.
+    /// Panics if `index > self.len()`
+    pub fn insert(&mut self, index: usize, val: T) {
+        let len = self.prefix().len;
+        assert!(index <= len);
+        if index == len {
+            self.push(val);
+            return;
+        }
+
+        self.grow(self.len_after(1));
+        let target_ptr = unsafe { self.inner_mut().body.as_mut_ptr().add(index) };
+        let copy_len = len - index;
+        let copy_dest = unsafe { target_ptr.add(1) };
+        unsafe { ptr::copy(target_ptr, copy_dest, copy_len) };
+        unsafe {
+            ptr::write(target_ptr, MaybeUninit::new(val));
+        };
+        self.prefix_mut().len = len + 1;
+    }
+
+    /// Creates an empty `ThinHeaderVec` with the specified capacity.
+    pub fn with_capacity(head: H, cap: usize) -> Self {
+        let layout = Self::get_layout(cap);
+        let ptr = allocator::allocate(&Global, layout).cast::<ThinPair<H, T>>();
+        unsafe {
+            ptr::write(ptr::addr_of_mut!((*ptr.as_ptr()).0), Prefix { len: 0, cap });
+            ptr::write(ptr::addr_of_mut!((*ptr.as_ptr()).1 .0), head);
+        }
+        Self { ptr }
+    }
+
+    /// Creates an empty `ThinHeaderVec`.
+    pub fn new(head: H) -> Self {
+        Self::with_capacity(head, MIN_CAP)
+    }
+
+    /// Shortens the vector to the given length.
+    /// Panics if `new_len > self.len()`.
+    pub fn truncate(&mut self, new_len: usize) {
+        let len = self.prefix().len;
+        assert!(new_len <= len);
+        if new_len == len {
+            return;
+        }
+
+        unsafe {
+            ptr::drop_in_place(&mut self.body[new_len..]);
+        }
+        unsafe { self.shrink(new_len) };
+        self.prefix_mut().len = new_len;
+    }
+
+    /// Resizes the vector.
+    /// If `new_len > self.len()`, the elements will be instantiated with the
+    /// given function.
+    pub fn resize_with(&mut self, new_len: usize, mut f: impl FnMut() -> T) {
+        let len = self.prefix().len;
+        if new_len < len {
+            self.truncate(new_len);
+        } else {
+            for _ in len..new_len {
+                self.push(f());
+            }
+        }
+    }
+
+    /// Creates a new instance of `ThinHeaderVec` from the given header and iterator.
+    pub fn from_iter<I: IntoIterator<Item = T>>(head: H, iter: I) -> Self {
+        let iter = iter.into_iter();
+        let (lower, _) = iter.size_hint();
+        let mut this = Self::with_capacity(head, lower);
+        this.extend(iter);
+        this
+    }
+
+    /// Reallocates so there is no excess capacity (i.e. capacity == length).
+    pub fn shrink_to_fit(&mut self) {
+        unsafe { self.realloc_exact(self.prefix().len) }
+    }
+
+    /// Reserve enough capacity to add at least `additional` elements without realllocating.
+    pub fn reserve(&mut self, additional: usize) {
+        unsafe { self.realloc_for(self.len_after(additional)) };
+    }
+
+    /// Reserve enough capacity to add  exactly `additional` elements without realllocating.
+    pub fn reserve_exact(&mut self, additional: usize) {
+        let new_cap = self.len_after(additional);
+        if new_cap <= self.capacity() {
+            return;
+        }
+        unsafe { self.realloc_exact(new_cap) };
+    }
+
+    /// Delete all items in the vector and reallocate so there is no excess capacity.
+    pub fn clear(&mut self) {
+        self.clear_in_place();
+        unsafe { self.realloc_exact(0) }
+    }
+
+    /// Delete all items in the vector without reallocating.
+    pub fn clear_in_place(&mut self) {
+        unsafe {
+            ptr::drop_in_place(&mut self.body);
+        }
+        self.prefix_mut().len = 0;
+    }
+
+    /// Converts a `HeaderVec` into a `ThinHeaderVec`.
+    /// The header and elements are moved within the allocation to make room for the length and
+    /// capacity, without reallocating when the alignment allows it.
+    pub fn from_header_vec(src: HeaderVec<H, T>) -> Self {
+        let (ptr, len, cap) = src.into_raw_parts();
+        let bytes_ptr = unsafe {
+            utils::realloc_shifted(
+                &Global,
+                ptr.as_ptr() as *mut u8,
+                HeaderSlice::<H, T>::layout_for_len(cap),
+                0,
+                Self::get_layout(cap),
+                Self::head_offset(),
+                HeaderSlice::<H, T>::layout_for_len(len).size(),
+            )
+        };
+        let ptr = unsafe { NonNull::new_unchecked(bytes_ptr as *mut ThinPair<H, T>) };
+        unsafe { ptr::write(ptr::addr_of_mut!((*ptr.as_ptr()).0), Prefix { len, cap }) };
+        Self { ptr }
+    }
+
+    /// Converts the vector into a `HeaderVec`.
+    /// The header and elements are moved to the start of the allocation, without reallocating
+    /// when the alignment allows it.
+    pub fn into_header_vec(self) -> HeaderVec<H, T> {
+        let Prefix { len, cap } = *self.prefix();
+        let bytes_ptr = unsafe {
+            utils::realloc_shifted(
+                &Global,
+                self.ptr.as_ptr() as *mut u8,
+                Self::get_layout(cap),
+                Self::head_offset(),
+                HeaderSlice::<H, T>::layout_for_len(cap),
+                0,
+                HeaderSlice::<H, T>::layout_for_len(len).size(),
+            )
+        };
+        mem::forget(self);
+        let ptr = unsafe { NonNull::new_unchecked(bytes_ptr as *mut Pair<H, MaybeUninit<T>>) };
+        unsafe { HeaderVec::from_raw_parts(ptr, len, cap) }
+    }
+
+    /// Converts the vector into a boxed `HeaderSlice`.
+    pub fn into_box(mut self) -> Box<HeaderSlice<H, T>> {
+        self.shrink_to_fit();
+        self.into_header_vec().into_box()
+    }
+
+    /// Creates a vector from a boxed `HeaderSlice`.
+    pub fn from_box(src: Box<HeaderSlice<H, T>>) -> Self {
+        Self::from_header_vec(HeaderVec::from_box(src))
+    }
+
+    /// Consumes the vector and returns an iterator of its values.
+    pub fn into_values(self) -> IntoValuesIter<H, T> {
+        self.into_header_vec().into_values()
+    }
+
+    /// Consumes the vector and returns its header and an iterator of its values.
+    pub fn into_header_values(self) -> (H, IntoValuesIter<H, T>) {
+        self.into_header_vec().into_header_values()
+    }
+
+    /// Deallocates the vector. Do not use the pointer after this.
+    unsafe fn dealloc(&mut self) {
+        let layout = Self::get_layout(self.prefix().cap);
+        allocator::deallocate(&Global, self.ptr.cast(), layout);
+    }
+}
+
+impl<H, T: Copy> ThinHeaderVec<H, T> {
+    /// Copies the contents of a slice into a new `ThinHeaderVec`.
+    pub fn copy_from_slice(head: H, src: &[T]) -> Self {
+        let mut this = Self::with_capacity(head, src.len());
+        this.extend_from_slice(src);
+        this
+    }
+
+    /// Copies the contents onto the end of the vector.
+    pub fn extend_from_slice(&mut self, src: &[T]) {
+        let old_len = self.prefix().len;
+        let new_len = self.len_after(src.len());
+        if new_len > self.capacity() {
+            self.grow(new_len);
+        }
+        let uninit_slice = &mut self.inner_mut().body[old_len..];
+        unsafe {
+            ptr::copy(
+                src.as_ptr() as *mut MaybeUninit<T>,
+                uninit_slice.as_mut_ptr(),
+                src.len(),
+            )
+        }
+        self.prefix_mut().len = new_len;
+    }
+}
+
+impl<H, T: Clone> ThinHeaderVec<H, T> {
+    /// Resize the vector. If `new_len > self.len()`, new entries will be cloned
+    /// from `val`.
+    pub fn resize(&mut self, new_len: usize, mut val: T) {
+        let len = self.prefix().len;
+        if new_len < len {
+            self.truncate(new_len);
+        } else if new_len > len {
+            for _ in len..new_len - 1 {
+                let next_val = val.clone();
+                self.push(val);
+                val = next_val;
+            }
+            self.push(val);
+        }
+    }
+}
+
+impl<H, T: Default> ThinHeaderVec<H, T> {
+    /// Resize the vector. If `new_len > self.len()`, new entries will use the
+    /// default value of `T`.
+    pub fn resize_default(&mut self, new_len: usize) {
+        self.resize_with(new_len, Default::default)
+    }
+}
+
+impl<H, T: Ord> ThinHeaderVec<H, T> {
+    /// Assuming the vector is sorted, insert the given value into its sorted position.
+    /// Behavior is undefined if the vector is not sorted.
+    pub fn insert_sorted(&mut self, val: T) {
+        let index = self.body.binary_search(&val).unwrap_or_else(|x| x);
+        self.insert(index, val);
+    }
+
+    /// Assuming the vector is sorted, insert the given value into its sorted position
+    /// if it does not already exist in the vector.
+    /// If an element already exists that compares equal to `val`, reaplce it with
+    /// `val` and return its original value.
+    /// Behavior is undefined if the vector is not sorted.
+    pub fn insert_or_replace_sorted(&mut self, val: T) -> Option<T> {
+        match self.body.binary_search(&val) {
+            Ok(i) => Some(mem::replace(&mut self.body[i], val)),
+            Err(i) => {
+                self.insert(i, val);
+                None
+            }
+        }
+    }
+}
+
+impl<H, T> Deref for ThinHeaderVec<H, T> {
+    type Target = HeaderSlice<H, T>;
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*self.as_ptr().as_ptr() }
+    }
+}
+
+impl<H, T> DerefMut for ThinHeaderVec<H, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { &mut *self.as_ptr().as_ptr() }
+    }
+}
+
+impl<H, T> AsRef<HeaderSlice<H, T>> for ThinHeaderVec<H, T> {
+    fn as_ref(&self) -> &HeaderSlice<H, T> {
+        self.deref()
+    }
+}
+
+impl<H, T> AsMut<HeaderSlice<H, T>> for ThinHeaderVec<H, T> {
+    fn as_mut(&mut self) -> &mut HeaderSlice<H, T> {
+        self.deref_mut()
+    }
+}
+
+impl<H, T> Borrow<HeaderSlice<H, T>> for ThinHeaderVec<H, T> {
+    fn borrow(&self) -> &HeaderSlice<H, T> {
+        self.deref()
+    }
+}
+
+impl<H, T> BorrowMut<HeaderSlice<H, T>> for ThinHeaderVec<H, T> {
+    fn borrow_mut(&mut self) -> &mut HeaderSlice<H, T> {
+        self.deref_mut()
+    }
+}
+
+impl<H, T> Drop for ThinHeaderVec<H, T> {
+    fn drop(&mut self) {
+        unsafe {
+            ptr::drop_in_place(self.deref_mut());
+            self.dealloc();
+        }
+    }
+}
+
+impl<H: Clone, T: Clone> Clone for ThinHeaderVec<H, T> {
+    fn clone(&self) -> Self {
+        Self::from_iter(self.head.clone(), self.body.iter().cloned())
+    }
+}
+
+impl<H, T> Extend<T> for ThinHeaderVec<H, T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for x in iter {
+            self.push(x);
+        }
+    }
+}
+
+impl<H, T, I: IntoIterator<Item = T>> AddAssign<I> for ThinHeaderVec<H, T> {
+    fn add_assign(&mut self, rhs: I) {
+        self.extend(rhs);
+    }
+}
+
+impl<H, T, I: IntoIterator<Item = T>> Add<I> for ThinHeaderVec<H, T> {
+    type Output = Self;
+    fn add(mut self, rhs: I) -> Self {
+        self += rhs;
+        self
+    }
+}
+
+impl<H, T, Rhs: ?Sized> PartialEq<Rhs> for ThinHeaderVec<H, T>
+where
+    H: PartialEq,
+    T: PartialEq,
+    Rhs: Borrow<HeaderSlice<H, T>>,
+{
+    fn eq(&self, rhs: &Rhs) -> bool {
+        self.deref() == rhs.borrow()
+    }
+}
+
+impl<H: Eq, T: Eq> Eq for ThinHeaderVec<H, T> {}
+
+impl<H, T, Rhs: ?Sized> PartialOrd<Rhs> for ThinHeaderVec<H, T>
+where
+    H: PartialOrd,
+    T: PartialOrd,
+    Rhs: Borrow<HeaderSlice<H, T>>,
+{
+    fn partial_cmp(&self, rhs: &Rhs) -> Option<Ordering> {
+        self.deref().partial_cmp(rhs.borrow())
+    }
+}
+
+impl<H: Ord, T: Ord> Ord for ThinHeaderVec<H, T> {
+    fn cmp(&self, rhs: &Self) -> Ordering {
+        self.deref().cmp(rhs.deref())
+    }
+}
+
+impl<H: Hash, T: Hash> Hash for ThinHeaderVec<H, T> {
+    fn hash<S: hash::Hasher>(&self, state: &mut S) {
+        self.deref().hash(state)
+    }
+}
+
+impl<H: Debug, T: Debug> Debug for ThinHeaderVec<H, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let hslice: &HeaderSlice<H, T> = self.deref();
+        hslice.fmt(f)
+    }
+}
+
+impl<H: Default, T> iter::FromIterator<T> for ThinHeaderVec<H, T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::from_iter(H::default(), iter)
+    }
+}
+
+impl<H, T> From<HeaderVec<H, T>> for ThinHeaderVec<H, T> {
+    fn from(src: HeaderVec<H, T>) -> Self {
+        Self::from_header_vec(src)
+    }
+}
+
+impl<H, T> From<ThinHeaderVec<H, T>> for HeaderVec<H, T> {
+    fn from(src: ThinHeaderVec<H, T>) -> Self {
+        src.into_header_vec()
+    }
+}
+
+impl<H, T> From<Box<HeaderSlice<H, T>>> for ThinHeaderVec<H, T> {
+    fn from(src: Box<HeaderSlice<H, T>>) -> Self {
+        Self::from_box(src)
+    }
+}
+
+impl<H, T> From<ThinHeaderVec<H, T>> for Box<HeaderSlice<H, T>> {
+    fn from(src: ThinHeaderVec<H, T>) -> Self {
+        src.into_box()
+    }
+}
+
+impl<H: Default, T> Default for ThinHeaderVec<H, T> {
+    fn default() -> Self {
+        Self::new(H::default())
+    }
+}
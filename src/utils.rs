@@ -1,4 +1,11 @@
-use core::ptr;
+#[cfg(feature = "alloc")]
+use crate::allocator::{self, Allocator};
+#[cfg(feature = "alloc")]
+use alloc::alloc::Layout;
+#[cfg(feature = "alloc")]
+use core::ops::{Bound, Range, RangeBounds};
+#[cfg(feature = "alloc")]
+use core::ptr::{self, NonNull};
 
 macro_rules! partial_ord_chain {
     ($($lhs:expr => $rhs:expr),* $(,)?) => {{
@@ -20,7 +27,32 @@ macro_rules! ord_chain {
     }};
 }
 
+/// Converts `range` to a `Range` within a slice of length `len`.
+/// Panics if the range is out of bounds or its start is after its end.
+#[cfg(feature = "alloc")]
+pub fn slice_range<R: RangeBounds<usize>>(range: R, len: usize) -> Range<usize> {
+    let start = match range.start_bound() {
+        Bound::Included(&start) => start,
+        Bound::Excluded(&start) => start.checked_add(1).expect("range start overflowed"),
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&end) => end.checked_add(1).expect("range end overflowed"),
+        Bound::Excluded(&end) => end,
+        Bound::Unbounded => len,
+    };
+    assert!(start <= end, "range start {} is after end {}", start, end);
+    assert!(
+        end <= len,
+        "range end {} is out of bounds for length {}",
+        end,
+        len
+    );
+    start..end
+}
+
 /// stand-in for the unstablem set_ptr_value feature
+#[cfg(feature = "alloc")]
 pub fn set_ptr_value<T: ?Sized>(mut ptr: *const T, value: *const u8) -> *const T {
     // obtain a pointer to the variable 'ptr':
     let ptr_ptr: *mut *const T = &mut ptr as *mut *const T;
@@ -33,6 +65,60 @@ pub fn set_ptr_value<T: ?Sized>(mut ptr: *const T, value: *const u8) -> *const T
 }
 
 /// stand-in for the unstablem set_ptr_value feature
+#[cfg(feature = "alloc")]
 pub fn set_ptr_value_mut<T: ?Sized>(ptr: *mut T, value: *mut u8) -> *mut T {
     set_ptr_value(ptr, value) as *mut T
 }
+
+/// Moves `size` bytes from `old_offset` in an allocation with `old_layout` to `new_offset` in an
+/// allocation with `new_layout`, returning the new allocation.
+/// The allocation is resized in place when both layouts share an alignment, and left alone if they
+/// also share a size. Otherwise the bytes are copied into a fresh allocation and the old one is
+/// freed.
+#[cfg(feature = "alloc")]
+pub unsafe fn realloc_shifted<A: Allocator>(
+    alloc: &A,
+    ptr: *mut u8,
+    old_layout: Layout,
+    old_offset: usize,
+    new_layout: Layout,
+    new_offset: usize,
+    size: usize,
+) -> *mut u8 {
+    let ptr = NonNull::new_unchecked(ptr);
+    if old_layout.align() != new_layout.align() {
+        let new_ptr = allocator::allocate(alloc, new_layout);
+        ptr::copy_nonoverlapping(
+            ptr.as_ptr().add(old_offset),
+            new_ptr.as_ptr().add(new_offset),
+            size,
+        );
+        allocator::deallocate(alloc, ptr, old_layout);
+        return new_ptr.as_ptr();
+    }
+
+    if new_layout.size() == old_layout.size() {
+        // nothing to resize, so the contents only move within the allocation
+        ptr::copy(
+            ptr.as_ptr().add(old_offset),
+            ptr.as_ptr().add(new_offset),
+            size,
+        );
+        return ptr.as_ptr();
+    }
+
+    if new_offset > old_offset {
+        // grow first so there's room to shift the contents to the right
+        let new_ptr = allocator::reallocate(alloc, ptr, old_layout, new_layout).as_ptr();
+        ptr::copy(new_ptr.add(old_offset), new_ptr.add(new_offset), size);
+        new_ptr
+    } else {
+        // shift the contents to the left before the tail of the allocation is released
+        ptr::copy(
+            ptr.as_ptr().add(old_offset),
+            ptr.as_ptr().add(new_offset),
+            size,
+        );
+        allocator::reallocate(alloc, ptr, old_layout, new_layout).as_ptr()
+    }
+}
@@ -1,33 +1,258 @@
+use crate::allocator::{self, Allocator, Global};
+use crate::error::{handle_reserve, TryReserveError};
+use crate::growth::{DefaultGrowth, GrowthPolicy};
 use crate::pair::Pair;
 use crate::slice::HeaderSlice;
 use crate::utils;
-use alloc::alloc::{alloc, dealloc, realloc, Layout};
+use alloc::alloc::Layout;
 use alloc::borrow::{Borrow, BorrowMut};
 use alloc::boxed::Box;
+use alloc::vec::Vec;
 use core::cmp::Ordering;
 use core::fmt::{self, Debug};
 use core::hash::{self, Hash};
 use core::iter;
+use core::marker::PhantomData;
 use core::mem::{self, MaybeUninit};
-use core::ops::{Add, AddAssign};
+use core::ops::{Add, AddAssign, RangeBounds};
 use core::ops::{Deref, DerefMut};
 use core::ptr::{self, NonNull};
+use core::slice;
 
-pub struct HeaderVec<H, T> {
+mod drain;
+mod extract_if;
+mod splice;
+
+pub use drain::Drain;
+pub use extract_if::ExtractIf;
+pub use splice::Splice;
+
+pub struct HeaderVec<H, T, A: Allocator = Global, P: GrowthPolicy = DefaultGrowth> {
     ptr: NonNull<Pair<H, MaybeUninit<T>>>,
     len: usize,
     cap: usize,
+    alloc: A,
+    _policy: PhantomData<P>,
 }
 
-const MIN_CAP: usize = 8;
+pub(crate) const MIN_CAP: usize = 8;
 
 impl<H, T> HeaderVec<H, T> {
-    /// The total reserved capacity of the vector.
+    /// Returns the raw parts (ptr, length, capacity) of the vector.
+    /// Reconstruct the vector by passing these values to `from_raw_parts`.
+    pub fn into_raw_parts(self) -> (NonNull<Pair<H, MaybeUninit<T>>>, usize, usize) {
+        let (ptr, len, cap, _) = self.into_raw_parts_with_alloc();
+        (ptr, len, cap)
+    }
+
+    /// Constructs an instance of this struct using the raw parts returned from `as_raw_parts` or
+    /// `into_raw_parts`.
+    ///
+    /// # Safety
+    /// The parts must describe a live allocation made by a `HeaderVec<H, T>`, and no other
+    /// instance may use them mutably while this one exists.
+    pub unsafe fn from_raw_parts(
+        ptr: NonNull<Pair<H, MaybeUninit<T>>>,
+        len: usize,
+        cap: usize,
+    ) -> Self {
+        Self::from_raw_parts_in(ptr, len, cap, Global)
+    }
+
+    /// Creates an empty `HeaderVec` with the specified capacity.
+    pub fn with_capacity(head: H, cap: usize) -> Self {
+        Self::with_capacity_in(head, cap, Global)
+    }
+
+    /// Creates an empty `HeaderVec` with the specified capacity, or returns an error if the
+    /// allocation fails.
+    pub fn try_with_capacity(head: H, cap: usize) -> Result<Self, TryReserveError> {
+        Self::try_with_capacity_in(head, cap, Global)
+    }
+
+    /// Creates an empty `HeaderVec`.
+    pub fn new(head: H) -> Self {
+        Self::new_in(head, Global)
+    }
+
+    /// Creates a new instance of `HeaderVec` from the given header and iterator.
+    pub fn from_iter<I: IntoIterator<Item = T>>(head: H, iter: I) -> Self {
+        Self::from_iter_in(head, iter, Global)
+    }
+
+    /// Creates a new instance of `HeaderVec` from the given header and iterator, or returns an
+    /// error if an allocation fails.
+    pub fn try_from_iter<I: IntoIterator<Item = T>>(
+        head: H,
+        iter: I,
+    ) -> Result<Self, TryReserveError> {
+        Self::try_from_iter_in(head, iter, Global)
+    }
+
+    /// Converts the vector into a boxed `HeaderSlice`.
+    pub fn into_box(mut self) -> Box<HeaderSlice<H, T>> {
+        self.shrink_to_fit();
+        let b = unsafe { Box::from_raw(self.as_ptr().as_ptr()) };
+        mem::forget(self);
+        b
+    }
+
+    /// Creates a vector from a boxed `HeaderSlice`.
+    pub fn from_box(src: Box<HeaderSlice<H, T>>) -> Self {
+        let len = src.body.len();
+        let ptr = NonNull::new(Box::into_raw(src) as *mut Pair<H, MaybeUninit<T>>).unwrap();
+        unsafe { Self::from_raw_parts(ptr, len, len) }
+    }
+
+    /// Creates a vector from a header and a `Vec`, reusing the `Vec`'s allocation. The buffer is
+    /// reallocated and the elements are moved back to make room for the header, or copied to a
+    /// new allocation if the header needs a larger alignment than the elements.
+    pub fn from_vec(head: H, src: Vec<T>) -> Self {
+        HeaderVec::from(src).map_head(|()| head)
+    }
+
+    /// Splits the vector into its header and a `Vec` of its elements, reusing the allocation. The
+    /// elements are moved to the start of the buffer, which only gives up the bytes of the header:
+    /// the `Vec` keeps the vector's capacity. If the header has a larger alignment than the
+    /// elements, they are copied to a new allocation of the same capacity instead.
+    pub fn into_head_and_vec(self) -> (H, Vec<T>) {
+        unsafe {
+            let head = ptr::read(&self.head);
+            let values = self.cast::<MaybeUninit<H>, T>().map_head(|_| ());
+            (head, values.into())
+        }
+    }
+
+    /// Copies the contents of a slice into a new `HeaderVec`.
+    /// Do not use or drop the contents of the original slice after this.
+    ///
+    /// # Safety
+    /// `src` must be valid for reads of `len` initialized elements.
+    pub unsafe fn copy_from_ptr_unsafe(head: H, src: *mut T, len: usize) -> Self {
+        Self::copy_from_ptr_unsafe_in(head, src, len, Global)
+    }
+}
+
+#[cfg(feature = "allocator-api2")]
+impl<H, T, A: Allocator, P: GrowthPolicy> HeaderVec<H, T, A, P> {
+    /// Converts the vector into a boxed `HeaderSlice` using the vector's allocator.
+    pub fn into_box_in(mut self) -> allocator_api2::boxed::Box<HeaderSlice<H, T>, A> {
+        self.shrink_to_fit();
+        let slice_ptr = self.as_ptr().as_ptr();
+        let (_, _, _, alloc) = self.into_raw_parts_with_alloc();
+        unsafe { allocator_api2::boxed::Box::from_raw_in(slice_ptr, alloc) }
+    }
+}
+
+#[cfg(feature = "allocator-api2")]
+impl<H, T, A: Allocator> HeaderVec<H, T, A> {
+    /// Creates a vector from a boxed `HeaderSlice`, taking ownership of its allocator.
+    pub fn from_box_in(src: allocator_api2::boxed::Box<HeaderSlice<H, T>, A>) -> Self {
+        let len = src.body.len();
+        let (ptr, alloc) = allocator_api2::boxed::Box::into_raw_with_allocator(src);
+        let ptr = unsafe { NonNull::new_unchecked(ptr as *mut Pair<H, MaybeUninit<T>>) };
+        unsafe { Self::from_raw_parts_in(ptr, len, len, alloc) }
+    }
+}
+
+impl<H, T, A: Allocator> HeaderVec<H, T, A> {
+    /// Constructs an instance of this struct using the raw parts returned from
+    /// `into_raw_parts_with_alloc`.
+    ///
+    /// # Safety
+    /// The parts must describe a live allocation made by a `HeaderVec<H, T, A>` using `alloc`,
+    /// and no other instance may use them mutably while this one exists.
+    pub unsafe fn from_raw_parts_in(
+        ptr: NonNull<Pair<H, MaybeUninit<T>>>,
+        len: usize,
+        cap: usize,
+        alloc: A,
+    ) -> Self {
+        Self {
+            ptr,
+            len,
+            cap: Self::effective_cap(cap),
+            alloc,
+            _policy: PhantomData,
+        }
+    }
+
+    /// Creates an empty `HeaderVec` with the specified capacity, using the given allocator.
+    pub fn with_capacity_in(head: H, cap: usize, alloc: A) -> Self {
+        handle_reserve(Self::try_with_capacity_in(head, cap, alloc))
+    }
+
+    /// Creates an empty `HeaderVec` with the specified capacity, using the given allocator.
+    /// Returns an error if the allocation fails.
+    pub fn try_with_capacity_in(head: H, cap: usize, alloc: A) -> Result<Self, TryReserveError> {
+        let layout = Self::try_get_layout(cap)?;
+        let ptr = allocator::try_allocate(&alloc, layout)?.cast::<Pair<H, MaybeUninit<T>>>();
+        unsafe { ptr::write(ptr::addr_of_mut!((*ptr.as_ptr()).0), head) }
+        Ok(Self {
+            ptr,
+            len: 0,
+            cap: Self::effective_cap(cap),
+            alloc,
+            _policy: PhantomData,
+        })
+    }
+
+    /// Creates an empty `HeaderVec` using the given allocator.
+    pub fn new_in(head: H, alloc: A) -> Self {
+        Self::with_capacity_in(head, MIN_CAP, alloc)
+    }
+
+    /// Creates a new instance of `HeaderVec` from the given header and iterator, using the given
+    /// allocator.
+    pub fn from_iter_in<I: IntoIterator<Item = T>>(head: H, iter: I, alloc: A) -> Self {
+        let iter = iter.into_iter();
+        let (lower, _) = iter.size_hint();
+        let mut this = Self::with_capacity_in(head, lower, alloc);
+        this.extend(iter);
+        this
+    }
+
+    /// Creates a new instance of `HeaderVec` from the given header and iterator, using the given
+    /// allocator. Returns an error if an allocation fails.
+    pub fn try_from_iter_in<I: IntoIterator<Item = T>>(
+        head: H,
+        iter: I,
+        alloc: A,
+    ) -> Result<Self, TryReserveError> {
+        let iter = iter.into_iter();
+        let (lower, _) = iter.size_hint();
+        let mut this = Self::try_with_capacity_in(head, lower, alloc)?;
+        this.try_extend(iter)?;
+        Ok(this)
+    }
+
+    /// Copies the contents of a slice into a new `HeaderVec` using the given allocator.
+    /// Do not use or drop the contents of the original slice after this.
+    ///
+    /// # Safety
+    /// `src` must be valid for reads of `len` initialized elements.
+    pub unsafe fn copy_from_ptr_unsafe_in(head: H, src: *mut T, len: usize, alloc: A) -> Self {
+        let mut this = Self::with_capacity_in(head, len, alloc);
+        let dest = this.inner_mut().body.as_mut_ptr() as *mut T;
+        ptr::copy_nonoverlapping(src, dest, len);
+        this.len = len;
+        this
+    }
+}
+
+impl<H, T, A: Allocator, P: GrowthPolicy> HeaderVec<H, T, A, P> {
+    /// The total reserved capacity of the vector. Always `usize::MAX` if `T` is zero-sized.
     pub fn capacity(&self) -> usize {
+        self.cap
+    }
+
+    /// Returns the capacity to store for a requested capacity of `cap`. Zero-sized elements
+    /// never take up space, so their capacity is always `usize::MAX`.
+    fn effective_cap(cap: usize) -> usize {
         if mem::size_of::<T>() == 0 {
             usize::MAX
         } else {
-            self.cap
+            cap
         }
     }
 
@@ -45,22 +270,20 @@ impl<H, T> HeaderVec<H, T> {
         (self.ptr, self.len, self.cap)
     }
 
-    /// Returns the raw parts (ptr, length, capacity) of the vector.
-    /// Reconstruct the vector by passing these values to `from_raw_parts`.
-    pub fn into_raw_parts(mut self) -> (NonNull<Pair<H, MaybeUninit<T>>>, usize, usize) {
-        let parts = self.as_raw_parts();
+    /// Returns the raw parts (ptr, length, capacity, allocator) of the vector.
+    /// Reconstruct the vector by passing these values to `from_raw_parts_in`.
+    pub fn into_raw_parts_with_alloc(
+        mut self,
+    ) -> (NonNull<Pair<H, MaybeUninit<T>>>, usize, usize, A) {
+        let (ptr, len, cap) = self.as_raw_parts();
+        let alloc = unsafe { ptr::read(&self.alloc) };
         mem::forget(self);
-        parts
+        (ptr, len, cap, alloc)
     }
 
-    /// Constructs an instance of this struct using the raw parts returned from `as_raw_parts` or
-    /// `into_raw_parts`.
-    pub unsafe fn from_raw_parts(
-        ptr: NonNull<Pair<H, MaybeUninit<T>>>,
-        len: usize,
-        cap: usize,
-    ) -> Self {
-        Self { ptr, len, cap }
+    /// Returns a reference to the vector's allocator.
+    pub fn allocator(&self) -> &A {
+        &self.alloc
     }
 
     /// Convert `ptr` to a mutable reference to a HeaderSlice with the entire capacity of the vector.
@@ -74,55 +297,81 @@ impl<H, T> HeaderVec<H, T> {
         HeaderSlice::<H, T>::layout_for_len(cap)
     }
 
+    /// Returns the `Layout` to be used when allocating the specified capacity, or an error if it
+    /// is too large.
+    fn try_get_layout(cap: usize) -> Result<Layout, TryReserveError> {
+        HeaderSlice::<H, T>::try_layout_for_len(cap).map_err(|_| TryReserveError::LayoutError)
+    }
+
     /// Reallocate so that the vector has the exact requested capacity
     /// unsafe because the new capacity may be less than self.len
-    unsafe fn realloc_exact(&mut self, count: usize) {
+    unsafe fn try_realloc_exact(&mut self, count: usize) -> Result<(), TryReserveError> {
         if mem::size_of::<T>() == 0 {
-            return;
+            return Ok(());
         }
         if count == self.cap {
-            return;
+            return Ok(());
         }
         let old_layout = Self::get_layout(self.cap);
-        let new_layout = Self::get_layout(count);
-        let bytes_ptr = realloc(self.ptr.as_ptr() as *mut u8, old_layout, new_layout.size());
-        let ptr = utils::set_ptr_value_mut(self.ptr.as_ptr(), bytes_ptr);
-        self.ptr = NonNull::new(ptr).unwrap();
+        let new_layout = Self::try_get_layout(count)?;
+        let bytes_ptr =
+            allocator::try_reallocate(&self.alloc, self.ptr.cast(), old_layout, new_layout)?;
+        let ptr = utils::set_ptr_value_mut(self.ptr.as_ptr(), bytes_ptr.as_ptr());
+        self.ptr = NonNull::new_unchecked(ptr);
         self.cap = count;
+        Ok(())
     }
 
-    /// Increase capacity so that about half the capacity is unused.
-    fn grow(&mut self, target_len: usize) {
-        let target_cap = (target_len * 2).max(self.cap);
-        unsafe { self.realloc_exact(target_cap) }
+    /// Reallocate so that the vector has the exact requested capacity
+    /// unsafe because the new capacity may be less than self.len
+    unsafe fn realloc_exact(&mut self, count: usize) {
+        handle_reserve(self.try_realloc_exact(count))
     }
 
-    /// Decrease capacity so that about half the capacity is unused.
-    /// unsafe because the new capacity may be less than self.len
-    unsafe fn shrink(&mut self, target_len: usize) {
-        let target_cap = (target_len * 2).max(MIN_CAP).min(self.cap);
-        self.realloc_exact(target_cap);
+    /// Increase capacity as chosen by the growth policy, if `target_len` elements don't fit.
+    fn try_grow(&mut self, target_len: usize) -> Result<(), TryReserveError> {
+        if target_len <= self.cap {
+            return Ok(());
+        }
+        let target_cap = P::grow(self.cap, target_len).max(target_len);
+        unsafe { self.try_realloc_exact(target_cap) }
     }
 
-    /// Reallocates if necessary to hold a vector of the given length
+    /// Increase capacity as chosen by the growth policy, if `target_len` elements don't fit.
+    fn grow(&mut self, target_len: usize) {
+        handle_reserve(self.try_grow(target_len))
+    }
+
+    /// Decrease capacity if the growth policy decides to now that only `target_len` elements are
+    /// in use.
     /// unsafe because the new capacity may be less than self.len
-    unsafe fn realloc_for(&mut self, len: usize) {
-        if len < self.len {
-            self.shrink(len);
-        } else if len > self.capacity() {
-            self.grow(len);
+    unsafe fn shrink(&mut self, target_len: usize) {
+        if let Some(target_cap) = P::shrink(self.cap, target_len) {
+            if target_cap < self.cap {
+                self.realloc_exact(target_cap.max(target_len));
+            }
         }
     }
 
     /// Push a value to the end of the vector.
     pub fn push(&mut self, val: T) {
-        let new_len = self.len + 1;
+        handle_reserve(self.try_push(val))
+    }
+
+    /// Push a value to the end of the vector, returning an error if the vector could not grow.
+    /// `val` is dropped if this fails.
+    pub fn try_push(&mut self, val: T) -> Result<(), TryReserveError> {
+        let new_len = self
+            .len
+            .checked_add(1)
+            .ok_or(TryReserveError::CapacityOverflow)?;
         if new_len > self.cap {
-            self.grow(new_len);
+            self.try_grow(new_len)?;
         }
         let index = self.len;
         self.inner_mut().body[index] = MaybeUninit::new(val);
         self.len = new_len;
+        Ok(())
     }
 
     /// Pop a value from the end of the vec, if there is one.
@@ -143,7 +392,7 @@ impl<H, T> HeaderVec<H, T> {
         if index >= self.len {
             return None;
         }
-        let target_ptr = &mut self.inner_mut().body[index] as *mut MaybeUninit<T>;
+        let target_ptr = unsafe { self.inner_mut().body.as_mut_ptr().add(index) };
         let val = unsafe { ptr::read(target_ptr) };
         let copy_len = self.len - index - 1;
         let copy_src = unsafe { target_ptr.add(1) };
@@ -174,14 +423,19 @@ impl<H, T> HeaderVec<H, T> {
     /// the right.
     /// Panics if `index > self.len()`
     pub fn insert(&mut self, index: usize, val: T) {
+        handle_reserve(self.try_insert(index, val))
+    }
+
+    /// Inserts an element at `index`, shifting all elements after `index` to
+    /// the right. Returns an error if the vector could not grow, in which case `val` is dropped.
+    /// Panics if `index > self.len()`
+    pub fn try_insert(&mut self, index: usize, val: T) -> Result<(), TryReserveError> {
         assert!(index <= self.len);
         if index == self.len {
-            self.push(val);
-            return;
+            return self.try_push(val);
         }
 
-        self.grow(self.len + 1);
-        // let target_ptr = &mut self.inner_mut().body[index] as *mut MaybeUninit<T>;
+        self.try_reserve(1)?;
         let target_ptr = unsafe { self.inner_mut().body.as_mut_ptr().add(index) };
         let copy_len = self.len - index;
         let copy_dest = unsafe { target_ptr.add(1) };
@@ -190,20 +444,7 @@ impl<H, T> HeaderVec<H, T> {
             ptr::write(target_ptr, MaybeUninit::new(val));
         };
         self.len += 1;
-    }
-
-    /// Creates an empty `HeaderVec` with the specified capacity.
-    pub fn with_capacity(head: H, cap: usize) -> Self {
-        let layout = Self::get_layout(cap);
-        let bytes_ptr = unsafe { alloc(layout) };
-        let mut ptr = NonNull::new(bytes_ptr as *mut Pair<H, MaybeUninit<T>>).unwrap();
-        unsafe { ptr::write(&mut ptr.as_mut().0 as *mut H, head) }
-        Self { ptr, len: 0, cap }
-    }
-
-    /// Creates an empty `HeaderVec`.
-    pub fn new(head: H) -> Self {
-        Self::with_capacity(head, MIN_CAP)
+        Ok(())
     }
 
     /// Shortens the vector to the given length.
@@ -224,23 +465,46 @@ impl<H, T> HeaderVec<H, T> {
     /// Resizes the vector.
     /// If `new_len > self.len()`, the elements will be instantiated with the
     /// given function.
-    pub fn resize_with(&mut self, new_len: usize, mut f: impl FnMut() -> T) {
+    pub fn resize_with(&mut self, new_len: usize, f: impl FnMut() -> T) {
         if new_len < self.len {
             self.truncate(new_len);
         } else {
-            for _ in self.len..new_len {
-                self.push(f());
+            let additional = new_len - self.len;
+            self.reserve(additional);
+            self.fill_spare(&mut iter::repeat_with(f).take(additional));
+        }
+    }
+
+    /// Moves values from `iter` into the spare capacity until either runs out.
+    /// The length is updated after each value, so if the iterator panics every value written so
+    /// far stays in the vector.
+    fn fill_spare<I: Iterator<Item = T>>(&mut self, iter: &mut I) {
+        let base = self.inner_mut().body.as_mut_ptr();
+        while self.len < self.cap {
+            match iter.next() {
+                Some(val) => unsafe { base.add(self.len).write(MaybeUninit::new(val)) },
+                None => break,
             }
+            self.len += 1;
         }
     }
 
-    /// Creates a new instance of `HeaderVec` from the given header and iterator.
-    pub fn from_iter<I: IntoIterator<Item = T>>(head: H, iter: I) -> Self {
-        let iter = iter.into_iter();
-        let (lower, _) = iter.size_hint();
-        let mut this = Self::with_capacity(head, lower);
-        this.extend(iter);
-        this
+    /// Appends the values of an iterator, returning an error if the vector could not grow.
+    /// Values that were already appended are kept if this fails.
+    pub fn try_extend<I: IntoIterator<Item = T>>(
+        &mut self,
+        iter: I,
+    ) -> Result<(), TryReserveError> {
+        let mut iter = iter.into_iter();
+        loop {
+            self.try_reserve(iter.size_hint().0)?;
+            self.fill_spare(&mut iter);
+            // the spare capacity ran out, or the size hint was too low
+            match iter.next() {
+                Some(x) => self.try_push(x)?,
+                None => return Ok(()),
+            }
+        }
     }
 
     /// Reallocates so there is no excess capacity (i.e. capacity == length).
@@ -248,61 +512,236 @@ impl<H, T> HeaderVec<H, T> {
         unsafe { self.realloc_exact(self.len) }
     }
 
-    /// Converts the vector into a boxed `HeaderSlice`.
-    pub fn into_box(mut self) -> Box<HeaderSlice<H, T>> {
-        self.shrink_to_fit();
-        let b = unsafe { Box::from_raw(self.as_ptr().as_ptr()) };
-        mem::forget(self);
-        b
+    /// Reallocates so the capacity is at most `min_capacity`, or the length if that is greater.
+    /// Does nothing if the capacity is already lower.
+    pub fn shrink_to(&mut self, min_capacity: usize) {
+        let target_cap = min_capacity.max(self.len);
+        if target_cap < self.capacity() {
+            unsafe { self.realloc_exact(target_cap) }
+        }
     }
 
-    /// Creates a vector from a boxed `HeaderSlice`.
-    pub fn from_box(src: Box<HeaderSlice<H, T>>) -> Self {
-        let len = src.body.len();
-        let ptr = NonNull::new(Box::into_raw(src) as *mut Pair<H, MaybeUninit<T>>).unwrap();
-        Self { ptr, len, cap: len }
+    /// Reserve enough capacity to add at least `additional` elements without realllocating.
+    pub fn reserve(&mut self, additional: usize) {
+        handle_reserve(self.try_reserve(additional))
     }
 
     /// Reserve enough capacity to add at least `additional` elements without realllocating.
-    pub fn reserve(&mut self, additional: usize) {
-        unsafe { self.realloc_for(self.len + additional) };
+    /// Returns an error if the allocation fails.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let new_len = self
+            .len
+            .checked_add(additional)
+            .ok_or(TryReserveError::CapacityOverflow)?;
+        if new_len > self.capacity() {
+            self.try_grow(new_len)?;
+        }
+        Ok(())
     }
 
     /// Reserve enough capacity to add  exactly `additional` elements without realllocating.
     pub fn reserve_exact(&mut self, additional: usize) {
-        let new_cap = self.len + additional;
-        if new_cap <= self.cap {
-            return;
+        handle_reserve(self.try_reserve_exact(additional))
+    }
+
+    /// Reserve enough capacity to add exactly `additional` elements without realllocating.
+    /// Returns an error if the allocation fails.
+    pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let new_cap = self
+            .len
+            .checked_add(additional)
+            .ok_or(TryReserveError::CapacityOverflow)?;
+        if new_cap <= self.capacity() {
+            return Ok(());
         }
-        unsafe { self.realloc_exact(new_cap) };
+        unsafe { self.try_realloc_exact(new_cap) }
     }
 
     /// Deallocates the vector. Do not use the pointer after this.
     unsafe fn dealloc(&mut self) {
-        dealloc(self.ptr.as_ptr() as *mut u8, Self::get_layout(self.cap));
+        let layout = Self::get_layout(self.cap);
+        allocator::deallocate(&self.alloc, self.ptr.cast(), layout);
     }
 
-    fn into_uninit(self) -> HeaderVec<MaybeUninit<H>, MaybeUninit<T>> {
-        unsafe { mem::transmute::<Self, HeaderVec<MaybeUninit<H>, MaybeUninit<T>>>(self) }
+    fn into_uninit(self) -> HeaderVec<MaybeUninit<H>, MaybeUninit<T>, A, P> {
+        unsafe { self.cast() }
     }
 
-    /// Consumes the vector and returns an iterator of its values.
-    pub fn into_values(self) -> IntoValuesIter<H, T> {
+    /// Consumes the vector and returns an iterator of its values. The header is dropped.
+    pub fn into_values(self) -> IntoValuesIter<H, T, A, P> {
         self.into_header_values().1
     }
 
     /// Consumes the vector and returns its header and an iterator of its values.
-    pub fn into_header_values(self) -> (H, IntoValuesIter<H, T>) {
-        let uninit = self.into_uninit();
-
-        let head = unsafe { mem::transmute_copy::<MaybeUninit<H>, H>(&uninit.head) };
-        let values = IntoValuesIter {
-            inner: uninit,
-            index: 0,
-        };
+    pub fn into_header_values(self) -> (H, IntoValuesIter<H, T, A, P>) {
+        let mut values = self.into_iter();
+        let head = values.take_head().unwrap();
         (head, values)
     }
 
+    /// Removes the elements in `range` from the vector, returning them in an iterator.
+    /// Elements after the range are shifted left once the iterator is dropped, and the capacity
+    /// is left unchanged.
+    /// Panics if the range is out of bounds.
+    pub fn drain<R: RangeBounds<usize>>(&mut self, range: R) -> Drain<'_, H, T, A, P> {
+        let range = utils::slice_range(range, self.len);
+        Drain::new(self, range.start, range.end)
+    }
+
+    /// Replaces the elements in `range` with the contents of `replace_with`, returning the removed
+    /// elements in an iterator.
+    /// The replacement happens when the returned iterator is dropped. The lower bound of
+    /// `replace_with`'s size hint is used to make room for the new elements, so the tail is only
+    /// moved once if it is accurate.
+    /// Panics if the range is out of bounds.
+    pub fn splice<R, I>(&mut self, range: R, replace_with: I) -> Splice<'_, H, T, I::IntoIter, A, P>
+    where
+        R: RangeBounds<usize>,
+        I: IntoIterator<Item = T>,
+    {
+        Splice::new(self.drain(range), replace_with.into_iter())
+    }
+
+    /// Keeps only the elements for which `f` returns `true`, preserving their order.
+    /// The vector is shrunk at most once, after all elements have been visited.
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut f: F) {
+        self.retain_mut(|val| f(val))
+    }
+
+    /// Keeps only the elements for which `f` returns `true`, preserving their order.
+    /// `f` may mutate the elements it visits.
+    /// The vector is shrunk at most once, after all elements have been visited.
+    pub fn retain_mut<F: FnMut(&mut T) -> bool>(&mut self, mut f: F) {
+        let original_len = self.len;
+        let mut guard = BackshiftOnDrop::new(self, 0);
+        while guard.processed < original_len {
+            let cur = unsafe { guard.base.add(guard.processed) };
+            if f(unsafe { &mut *cur }) {
+                if guard.deleted > 0 {
+                    unsafe { ptr::copy_nonoverlapping(cur, cur.sub(guard.deleted), 1) };
+                }
+                guard.processed += 1;
+            } else {
+                // count the element as processed first so a panic while dropping it won't drop
+                // it again
+                guard.processed += 1;
+                guard.deleted += 1;
+                unsafe { ptr::drop_in_place(cur) };
+            }
+        }
+        drop(guard);
+        if self.len < original_len {
+            unsafe { self.shrink(self.len) };
+        }
+    }
+
+    /// Removes consecutive elements for which `same_bucket(current, previous)` returns `true`,
+    /// keeping the first of each run.
+    /// The vector is shrunk at most once, after all elements have been visited.
+    pub fn dedup_by<F: FnMut(&mut T, &mut T) -> bool>(&mut self, mut same_bucket: F) {
+        let original_len = self.len;
+        if original_len <= 1 {
+            return;
+        }
+        let mut guard = BackshiftOnDrop::new(self, 1);
+        while guard.processed < original_len {
+            let cur = unsafe { guard.base.add(guard.processed) };
+            let prev = unsafe { cur.sub(guard.deleted + 1) };
+            if same_bucket(unsafe { &mut *cur }, unsafe { &mut *prev }) {
+                guard.processed += 1;
+                guard.deleted += 1;
+                unsafe { ptr::drop_in_place(cur) };
+            } else {
+                if guard.deleted > 0 {
+                    unsafe { ptr::copy_nonoverlapping(cur, prev.add(1), 1) };
+                }
+                guard.processed += 1;
+            }
+        }
+        drop(guard);
+        if self.len < original_len {
+            unsafe { self.shrink(self.len) };
+        }
+    }
+
+    /// Removes consecutive elements that map to the same key, keeping the first of each run.
+    pub fn dedup_by_key<K: PartialEq, F: FnMut(&mut T) -> K>(&mut self, mut key: F) {
+        self.dedup_by(|a, b| key(a) == key(b))
+    }
+
+    /// Splits the vector in two at `at`, returning a new vector with the header `new_head` that
+    /// holds the elements from `at` onwards.
+    /// Panics if `at > self.len()`.
+    pub fn split_off(&mut self, at: usize, new_head: H) -> Self
+    where
+        A: Clone,
+    {
+        assert!(
+            at <= self.len,
+            "split index {} is out of bounds for length {}",
+            at,
+            self.len
+        );
+        let tail_len = self.len - at;
+        let mut other = HeaderVec::with_capacity_in(new_head, tail_len, self.alloc.clone())
+            .with_growth_policy();
+        unsafe {
+            let src = (self.inner_mut().body.as_ptr() as *const T).add(at);
+            let dst = other.inner_mut().body.as_mut_ptr() as *mut T;
+            ptr::copy_nonoverlapping(src, dst, tail_len);
+            other.len = tail_len;
+            self.len = at;
+            self.shrink(at);
+        }
+        other
+    }
+
+    /// Moves all the elements of `other` to the end of this vector, leaving `other` empty.
+    /// The header and capacity of `other` are left unchanged.
+    pub fn append<H2, B: Allocator, Q: GrowthPolicy>(
+        &mut self,
+        other: &mut HeaderVec<H2, T, B, Q>,
+    ) {
+        let count = other.len;
+        self.reserve(count);
+        let new_len = self.len + count;
+        unsafe {
+            let src = other.inner_mut().body.as_ptr() as *const T;
+            let dst = (self.inner_mut().body.as_mut_ptr() as *mut T).add(self.len);
+            ptr::copy_nonoverlapping(src, dst, count);
+        }
+        other.len = 0;
+        self.len = new_len;
+    }
+
+    /// Moves all the elements of `other` to the end of this vector, then deallocates `other` and
+    /// returns its header.
+    pub fn append_owned<H2, B: Allocator, Q: GrowthPolicy>(
+        &mut self,
+        mut other: HeaderVec<H2, T, B, Q>,
+    ) -> H2 {
+        self.append(&mut other);
+        unsafe {
+            let head = ptr::read(&other.head);
+            other.dealloc_without_dropping();
+            head
+        }
+    }
+
+    /// Returns an iterator that removes and yields the elements in `range` for which `pred`
+    /// returns `true`.
+    /// Elements are only visited as the iterator is advanced; any elements that haven't been
+    /// visited when it is dropped are kept.
+    /// Panics if the range is out of bounds.
+    pub fn extract_if<R, F>(&mut self, range: R, pred: F) -> ExtractIf<'_, H, T, F, A, P>
+    where
+        R: RangeBounds<usize>,
+        F: FnMut(&mut T) -> bool,
+    {
+        let range = utils::slice_range(range, self.len);
+        ExtractIf::new(self, range.start, range.end, pred)
+    }
+
     /// Delete all items in the vector and reallocate so there is no excess capacity.
     pub fn clear(&mut self) {
         self.clear_in_place();
@@ -317,52 +756,193 @@ impl<H, T> HeaderVec<H, T> {
         self.len = 0;
     }
 
+    /// Deallocates the vector without dropping its header or elements.
+    ///
+    /// # Safety
+    /// The header and elements are leaked; they must have been moved out or be safe to forget.
     pub unsafe fn dealloc_without_dropping(mut self) {
         self.dealloc();
         mem::forget(self);
     }
 
-    /// Copies the contents of a slice into a new `HeaderVec`.
-    /// Do not use or drop the contents of the original slice after this.
-    pub unsafe fn copy_from_ptr_unsafe(head: H, src: *mut T, len: usize) -> Self {
-        let mut this = Self::with_capacity(head, len);
-        let dest = this.body.as_mut_ptr();
-        ptr::copy_nonoverlapping(src, dest, len);
-        this.len = len;
-        this
+    /// Converts the vector to use a different `GrowthPolicy`, without reallocating.
+    pub fn with_growth_policy<Q: GrowthPolicy>(self) -> HeaderVec<H, T, A, Q> {
+        let (ptr, len, cap, alloc) = self.into_raw_parts_with_alloc();
+        HeaderVec {
+            ptr,
+            len,
+            cap,
+            alloc,
+            _policy: PhantomData,
+        }
     }
 
-    unsafe fn cast<H2, T2>(self) -> HeaderVec<H2, T2> {
-        let v = HeaderVec {
-            ptr: self.ptr.cast(),
-            len: self.len,
-            cap: self.cap,
-        };
-        mem::forget(self);
-        v
+    /// Replaces the header with the result of calling `f` on it. The allocation is reused, and
+    /// only reallocated if the header's size or alignment moves the body.
+    /// If `f` panics, the elements are dropped and the allocation is freed.
+    pub fn map_head<H2>(self, f: impl FnOnce(H) -> H2) -> HeaderVec<H2, T, A, P> {
+        unsafe {
+            // dropping this drops the elements and frees the allocation, but not the header
+            let vec = self.cast::<MaybeUninit<H>, T>();
+            let head = f(vec.head.assume_init_read());
+            let mut vec = vec.relayout::<H2, T>();
+            vec.head.write(head);
+            vec.assume_init_head()
+        }
+    }
+
+    /// Converts each element with `f`, keeping the header. If `U` has the same size as `T` the
+    /// elements are converted in place and the allocation is reused; otherwise they are moved into
+    /// a new allocation sized for `U`.
+    /// If `f` panics, the header and every remaining element are dropped and the memory is freed.
+    pub fn map_values<U>(self, f: impl FnMut(T) -> U) -> HeaderVec<H, U, A, P> {
+        if mem::size_of::<U>() != mem::size_of::<T>() {
+            return self.map_values_into_new(f);
+        }
+        unsafe {
+            // the header is moved out while the body is moved, so dropping `vec` only frees the
+            // allocation
+            let mut vec = self.cast::<MaybeUninit<H>, MaybeUninit<T>>();
+            let head = vec.head.assume_init_read();
+            let mut vec = if mem::align_of::<U>() <= mem::align_of::<T>() {
+                // every slot for a `T` is also aligned for a `U`
+                let body = vec.inner_mut().body.as_mut_ptr() as *mut T;
+                MapOnDrop::<_, _, A>::new(body, body as *mut U, vec.len, None).run(f);
+                vec.relayout::<H, MaybeUninit<U>>()
+            } else {
+                // move the elements to slots aligned for `U`, which are also aligned for `T`
+                let mut vec = vec.relayout::<H, MaybeUninit<U>>();
+                let body = vec.inner_mut().body.as_mut_ptr() as *mut U;
+                MapOnDrop::<_, _, A>::new(body as *mut T, body, vec.len, None).run(f);
+                vec
+            };
+            vec.head.write(head);
+            vec.assume_init()
+        }
+    }
+
+    /// Implements `map_values` for element types of different sizes, by mapping into a new
+    /// allocation.
+    fn map_values_into_new<U>(self, f: impl FnMut(T) -> U) -> HeaderVec<H, U, A, P> {
+        let len = self.len;
+        let cap = HeaderVec::<H, U, A, P>::effective_cap(len);
+        let new_layout = HeaderVec::<H, U, A, P>::get_layout(cap);
+        unsafe {
+            let mut vec = self.cast::<H, MaybeUninit<T>>();
+            let new_ptr = handle_reserve(allocator::try_allocate(&vec.alloc, new_layout));
+            let new_ptr = new_ptr.cast::<Pair<H, MaybeUninit<U>>>();
+            let src = vec.inner_mut().body.as_mut_ptr() as *mut T;
+            let dst = ptr::addr_of_mut!((*new_ptr.as_ptr()).1) as *mut U;
+            MapOnDrop::new(
+                src,
+                dst,
+                len,
+                Some((&vec.alloc, new_ptr.cast(), new_layout)),
+            )
+            .run(f);
+
+            ptr::write(
+                ptr::addr_of_mut!((*new_ptr.as_ptr()).0),
+                ptr::read(&vec.head),
+            );
+            let (old_ptr, _, old_cap, alloc) = vec.into_raw_parts_with_alloc();
+            let old_layout = Self::get_layout(old_cap);
+            allocator::deallocate(&alloc, old_ptr.cast(), old_layout);
+            HeaderVec {
+                ptr: new_ptr,
+                len,
+                cap,
+                alloc,
+                _policy: PhantomData,
+            }
+        }
+    }
+
+    /// Returns the offset of the body from the start of the allocation.
+    fn body_offset() -> usize {
+        Layout::new::<H>().extend(Layout::new::<T>()).unwrap().1
+    }
+
+    /// Moves the body to where a `HeaderVec<H2, T2>` keeps it, changing the allocation to that
+    /// type's layout for the same capacity with `utils::realloc_shifted`. The header is not moved,
+    /// so the caller must write it before the result is used.
+    ///
+    /// # Safety
+    /// `T2` must have the same size as `T`, and the elements must be valid for `T2` before the
+    /// result is used or dropped.
+    unsafe fn relayout<H2, T2>(self) -> HeaderVec<MaybeUninit<H2>, T2, A, P> {
+        debug_assert_eq!(mem::size_of::<T>(), mem::size_of::<T2>());
+        let (ptr, len, cap, alloc) = self.into_raw_parts_with_alloc();
+        let new_ptr = utils::realloc_shifted(
+            &alloc,
+            ptr.as_ptr() as *mut u8,
+            Self::get_layout(cap),
+            Self::body_offset(),
+            HeaderVec::<H2, T2, A, P>::get_layout(cap),
+            HeaderVec::<H2, T2, A, P>::body_offset(),
+            len * mem::size_of::<T>(),
+        );
+        HeaderVec {
+            ptr: NonNull::new_unchecked(new_ptr).cast(),
+            len,
+            cap,
+            alloc,
+            _policy: PhantomData,
+        }
+    }
+
+    unsafe fn cast<H2, T2>(self) -> HeaderVec<H2, T2, A, P> {
+        let (ptr, len, cap, alloc) = self.into_raw_parts_with_alloc();
+        HeaderVec {
+            ptr: ptr.cast(),
+            len,
+            cap,
+            alloc,
+            _policy: PhantomData,
+        }
     }
 }
 
 impl<H, T> HeaderVec<H, MaybeUninit<T>> {
     pub fn new_uninit_values(head: H, len: usize) -> Self {
-        let mut this = Self::with_capacity(head, len);
+        Self::new_uninit_values_in(head, len, Global)
+    }
+}
+
+impl<H, T, A: Allocator> HeaderVec<H, MaybeUninit<T>, A> {
+    pub fn new_uninit_values_in(head: H, len: usize, alloc: A) -> Self {
+        let mut this = Self::with_capacity_in(head, len, alloc);
         this.len = len;
         this
     }
+}
 
-    pub unsafe fn assume_init_values(self) -> HeaderVec<H, T> {
+impl<H, T, A: Allocator, P: GrowthPolicy> HeaderVec<H, MaybeUninit<T>, A, P> {
+    /// Converts to a vector of initialized values.
+    ///
+    /// # Safety
+    /// Every element in the body must be initialized.
+    pub unsafe fn assume_init_values(self) -> HeaderVec<H, T, A, P> {
         self.cast()
     }
 }
 
-impl<H, T> HeaderVec<MaybeUninit<H>, MaybeUninit<T>> {
-    pub unsafe fn assume_init(self) -> HeaderVec<H, T> {
+impl<H, T, A: Allocator, P: GrowthPolicy> HeaderVec<MaybeUninit<H>, MaybeUninit<T>, A, P> {
+    /// Converts to a vector with an initialized header and values.
+    ///
+    /// # Safety
+    /// The header and every element in the body must be initialized.
+    pub unsafe fn assume_init(self) -> HeaderVec<H, T, A, P> {
         self.cast()
     }
 }
 
-impl<H, T> HeaderVec<MaybeUninit<H>, T> {
-    pub unsafe fn assume_init_head(self) -> HeaderVec<H, T> {
+impl<H, T, A: Allocator, P: GrowthPolicy> HeaderVec<MaybeUninit<H>, T, A, P> {
+    /// Converts to a vector with an initialized header.
+    ///
+    /// # Safety
+    /// The header must be initialized.
+    pub unsafe fn assume_init_head(self) -> HeaderVec<H, T, A, P> {
         self.cast()
     }
 }
@@ -370,46 +950,55 @@ impl<H, T> HeaderVec<MaybeUninit<H>, T> {
 impl<H, T: Copy> HeaderVec<H, T> {
     /// Copies the contents of a slice into a new `HeaderVec`.
     pub fn copy_from_slice(head: H, src: &[T]) -> Self {
-        unsafe { Self::copy_from_ptr_unsafe(head, src.as_ptr() as *mut T, src.len()) }
+        Self::copy_from_slice_in(head, src, Global)
     }
+}
 
-    /// Copies the contents onto the end of the vector.
+impl<H, T: Copy, A: Allocator> HeaderVec<H, T, A> {
+    /// Copies the contents of a slice into a new `HeaderVec` using the given allocator.
+    pub fn copy_from_slice_in(head: H, src: &[T], alloc: A) -> Self {
+        unsafe { Self::copy_from_ptr_unsafe_in(head, src.as_ptr() as *mut T, src.len(), alloc) }
+    }
+}
+
+impl<H, T: Clone, A: Allocator, P: GrowthPolicy> HeaderVec<H, T, A, P> {
+    /// Clones the contents of a slice onto the end of the vector.
     pub fn extend_from_slice(&mut self, src: &[T]) {
-        let new_len = self.len + src.len();
-        if new_len > self.cap {
-            self.grow(new_len);
-        }
-        let old_len = self.len;
-        let uninit_slice = &mut self.inner_mut().body[old_len..];
-        unsafe {
-            ptr::copy(
-                src.as_ptr() as *mut MaybeUninit<T>,
-                uninit_slice.as_mut_ptr(),
-                src.len(),
-            )
+        self.reserve(src.len());
+        self.fill_spare(&mut src.iter().cloned());
+    }
+
+    /// Clones the elements in `range` onto the end of the vector.
+    /// Panics if the range is out of bounds.
+    pub fn extend_from_within<R: RangeBounds<usize>>(&mut self, range: R) {
+        let range = utils::slice_range(range, self.len);
+        self.reserve(range.len());
+        let base = self.inner_mut().body.as_mut_ptr() as *mut T;
+        for i in range {
+            unsafe {
+                let val = (*base.add(i)).clone();
+                base.add(self.len).write(val);
+            }
+            self.len += 1;
         }
-        self.len = new_len;
     }
-}
 
-impl<H, T: Clone> HeaderVec<H, T> {
     /// Resize the vector. If `new_len > self.len()`, new entries will be cloned
     /// from `val`.
-    pub fn resize(&mut self, new_len: usize, mut val: T) {
+    pub fn resize(&mut self, new_len: usize, val: T) {
         if new_len < self.len {
             self.truncate(new_len);
         } else if new_len > self.len {
-            for _ in self.len..new_len - 1 {
-                let next_val = val.clone();
-                self.push(val);
-                val = next_val;
-            }
-            self.push(val);
+            let additional = new_len - self.len;
+            self.reserve(additional);
+            // clone into all but the last new slot, then move `val` into that one
+            self.fill_spare(&mut iter::repeat(&val).take(additional - 1).cloned());
+            self.fill_spare(&mut iter::once(val));
         }
     }
 }
 
-impl<H, T: Default> HeaderVec<H, T> {
+impl<H, T: Default, A: Allocator, P: GrowthPolicy> HeaderVec<H, T, A, P> {
     /// Resize the vector. If `new_len > self.len()`, new entries will use the
     /// default value of `T`.
     pub fn resize_default(&mut self, new_len: usize) {
@@ -417,7 +1006,14 @@ impl<H, T: Default> HeaderVec<H, T> {
     }
 }
 
-impl<H, T: Ord> HeaderVec<H, T> {
+impl<H, T: PartialEq, A: Allocator, P: GrowthPolicy> HeaderVec<H, T, A, P> {
+    /// Removes consecutive repeated elements, keeping the first of each run.
+    pub fn dedup(&mut self) {
+        self.dedup_by(|a, b| a == b)
+    }
+}
+
+impl<H, T: Ord, A: Allocator, P: GrowthPolicy> HeaderVec<H, T, A, P> {
     /// Assuming the vector is sorted, insert the given value into its sorted position.
     /// Behavior is undefined if the vector is not sorted.
     pub fn insert_sorted(&mut self, val: T) {
@@ -441,44 +1037,140 @@ impl<H, T: Ord> HeaderVec<H, T> {
     }
 }
 
-impl<H, T> Deref for HeaderVec<H, T> {
+/// Tracks a pass converting the elements at `src` into the elements at `dst`, which may be the same
+/// memory. Elements before `mapped` have been converted. On drop, which only happens if the
+/// conversion panicked, the converted elements and the ones after the element being converted are
+/// dropped, and `new_block` is freed if the output has its own allocation.
+struct MapOnDrop<'a, T, U, A: Allocator> {
+    src: *mut T,
+    dst: *mut U,
+    mapped: usize,
+    len: usize,
+    new_block: Option<(&'a A, NonNull<u8>, Layout)>,
+}
+
+impl<'a, T, U, A: Allocator> MapOnDrop<'a, T, U, A> {
+    fn new(
+        src: *mut T,
+        dst: *mut U,
+        len: usize,
+        new_block: Option<(&'a A, NonNull<u8>, Layout)>,
+    ) -> Self {
+        Self {
+            src,
+            dst,
+            mapped: 0,
+            len,
+            new_block,
+        }
+    }
+
+    /// Converts every element.
+    /// unsafe because `src` must hold `len` initialized elements, and `dst` must have room for
+    /// them.
+    unsafe fn run(mut self, mut f: impl FnMut(T) -> U) {
+        while self.mapped < self.len {
+            let val = ptr::read(self.src.add(self.mapped));
+            ptr::write(self.dst.add(self.mapped), f(val));
+            self.mapped += 1;
+        }
+        mem::forget(self);
+    }
+}
+
+impl<T, U, A: Allocator> Drop for MapOnDrop<'_, T, U, A> {
+    fn drop(&mut self) {
+        unsafe {
+            ptr::drop_in_place(ptr::slice_from_raw_parts_mut(self.dst, self.mapped));
+            let rest = self.src.add(self.mapped + 1);
+            let rest_len = self.len - self.mapped - 1;
+            ptr::drop_in_place(ptr::slice_from_raw_parts_mut(rest, rest_len));
+            if let Some((alloc, ptr, layout)) = self.new_block {
+                allocator::deallocate(alloc, ptr, layout);
+            }
+        }
+    }
+}
+
+/// Tracks an in-place filtering pass over a `HeaderVec`.
+/// Elements before `processed` have been visited, and `deleted` of them have been dropped, leaving
+/// a gap before `processed`. On drop, the unvisited elements are shifted back to close the gap and
+/// the length is updated, so an unwinding callback can't cause double drops or leave holes.
+struct BackshiftOnDrop<'a, H, T, A: Allocator, P: GrowthPolicy> {
+    vec: &'a mut HeaderVec<H, T, A, P>,
+    base: *mut T,
+    processed: usize,
+    deleted: usize,
+    original_len: usize,
+}
+
+impl<'a, H, T, A: Allocator, P: GrowthPolicy> BackshiftOnDrop<'a, H, T, A, P> {
+    fn new(vec: &'a mut HeaderVec<H, T, A, P>, processed: usize) -> Self {
+        let original_len = vec.len;
+        let base = vec.inner_mut().body.as_mut_ptr() as *mut T;
+        Self {
+            vec,
+            base,
+            processed,
+            deleted: 0,
+            original_len,
+        }
+    }
+}
+
+impl<H, T, A: Allocator, P: GrowthPolicy> Drop for BackshiftOnDrop<'_, H, T, A, P> {
+    fn drop(&mut self) {
+        if self.deleted > 0 {
+            unsafe {
+                ptr::copy(
+                    self.base.add(self.processed),
+                    self.base.add(self.processed - self.deleted),
+                    self.original_len - self.processed,
+                );
+            }
+        }
+        self.vec.len = self.original_len - self.deleted;
+    }
+}
+
+impl<H, T, A: Allocator, P: GrowthPolicy> Deref for HeaderVec<H, T, A, P> {
     type Target = HeaderSlice<H, T>;
     fn deref(&self) -> &Self::Target {
         unsafe { &*self.as_ptr().as_ptr() }
     }
 }
 
-impl<H, T> DerefMut for HeaderVec<H, T> {
+impl<H, T, A: Allocator, P: GrowthPolicy> DerefMut for HeaderVec<H, T, A, P> {
     fn deref_mut(&mut self) -> &mut Self::Target {
         unsafe { &mut *self.as_ptr().as_ptr() }
     }
 }
 
-impl<H, T> AsRef<HeaderSlice<H, T>> for HeaderVec<H, T> {
+impl<H, T, A: Allocator, P: GrowthPolicy> AsRef<HeaderSlice<H, T>> for HeaderVec<H, T, A, P> {
     fn as_ref(&self) -> &HeaderSlice<H, T> {
         self.deref()
     }
 }
 
-impl<H, T> AsMut<HeaderSlice<H, T>> for HeaderVec<H, T> {
+impl<H, T, A: Allocator, P: GrowthPolicy> AsMut<HeaderSlice<H, T>> for HeaderVec<H, T, A, P> {
     fn as_mut(&mut self) -> &mut HeaderSlice<H, T> {
         self.deref_mut()
     }
 }
 
-impl<H, T> Borrow<HeaderSlice<H, T>> for HeaderVec<H, T> {
+impl<H, T, A: Allocator, P: GrowthPolicy> Borrow<HeaderSlice<H, T>> for HeaderVec<H, T, A, P> {
     fn borrow(&self) -> &HeaderSlice<H, T> {
         self.deref()
     }
 }
 
-impl<H, T> BorrowMut<HeaderSlice<H, T>> for HeaderVec<H, T> {
+impl<H, T, A: Allocator, P: GrowthPolicy> BorrowMut<HeaderSlice<H, T>> for HeaderVec<H, T, A, P> {
     fn borrow_mut(&mut self) -> &mut HeaderSlice<H, T> {
         self.deref_mut()
     }
 }
 
-impl<H, T> Drop for HeaderVec<H, T> {
+impl<H, T, A: Allocator, P: GrowthPolicy> Drop for HeaderVec<H, T, A, P> {
     fn drop(&mut self) {
         unsafe {
             ptr::drop_in_place(self.deref_mut());
@@ -487,27 +1179,53 @@ impl<H, T> Drop for HeaderVec<H, T> {
     }
 }
 
-impl<H: Clone, T: Clone> Clone for HeaderVec<H, T> {
+impl<H: Clone, T: Clone, A: Allocator + Clone, P: GrowthPolicy> Clone for HeaderVec<H, T, A, P> {
     fn clone(&self) -> Self {
-        Self::from_iter(self.head.clone(), self.body.iter().cloned())
+        let mut out = HeaderVec::with_capacity_in(self.head.clone(), self.len, self.alloc.clone())
+            .with_growth_policy();
+        out.extend_from_slice(&self.body);
+        out
+    }
+
+    /// Clones `source` into this vector, reusing its allocation where possible.
+    fn clone_from(&mut self, source: &Self) {
+        self.head.clone_from(&source.head);
+        if self.len > source.len {
+            let old_len = mem::replace(&mut self.len, source.len);
+            unsafe {
+                let tail = self.inner_mut().body.get_unchecked_mut(source.len..old_len);
+                ptr::drop_in_place(tail as *mut [MaybeUninit<T>] as *mut [T]);
+            }
+        }
+        let (init, tail) = source.body.split_at(self.len);
+        self.body.clone_from_slice(init);
+        self.extend_from_slice(tail);
     }
 }
 
-impl<H, T> Extend<T> for HeaderVec<H, T> {
+impl<H, T, A: Allocator, P: GrowthPolicy> Extend<T> for HeaderVec<H, T, A, P> {
     fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
-        for x in iter {
-            self.push(x);
-        }
+        handle_reserve(self.try_extend(iter))
+    }
+}
+
+impl<'a, H, T: Copy + 'a, A: Allocator, P: GrowthPolicy> Extend<&'a T> for HeaderVec<H, T, A, P> {
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied())
     }
 }
 
-impl<H, T, I: IntoIterator<Item = T>> AddAssign<I> for HeaderVec<H, T> {
+impl<H, T, A: Allocator, P: GrowthPolicy, I: IntoIterator<Item = T>> AddAssign<I>
+    for HeaderVec<H, T, A, P>
+{
     fn add_assign(&mut self, rhs: I) {
         self.extend(rhs);
     }
 }
 
-impl<H, T, I: IntoIterator<Item = T>> Add<I> for HeaderVec<H, T> {
+impl<H, T, A: Allocator, P: GrowthPolicy, I: IntoIterator<Item = T>> Add<I>
+    for HeaderVec<H, T, A, P>
+{
     type Output = Self;
     fn add(mut self, rhs: I) -> Self {
         self += rhs;
@@ -515,7 +1233,7 @@ impl<H, T, I: IntoIterator<Item = T>> Add<I> for HeaderVec<H, T> {
     }
 }
 
-impl<H, T, Rhs: ?Sized> PartialEq<Rhs> for HeaderVec<H, T>
+impl<H, T, A: Allocator, P: GrowthPolicy, Rhs: ?Sized> PartialEq<Rhs> for HeaderVec<H, T, A, P>
 where
     H: PartialEq,
     T: PartialEq,
@@ -526,9 +1244,9 @@ where
     }
 }
 
-impl<H: Eq, T: Eq> Eq for HeaderVec<H, T> {}
+impl<H: Eq, T: Eq, A: Allocator, P: GrowthPolicy> Eq for HeaderVec<H, T, A, P> {}
 
-impl<H, T, Rhs: ?Sized> PartialOrd<Rhs> for HeaderVec<H, T>
+impl<H, T, A: Allocator, P: GrowthPolicy, Rhs: ?Sized> PartialOrd<Rhs> for HeaderVec<H, T, A, P>
 where
     H: PartialOrd,
     T: PartialOrd,
@@ -539,28 +1257,30 @@ where
     }
 }
 
-impl<H: Ord, T: Ord> Ord for HeaderVec<H, T> {
+impl<H: Ord, T: Ord, A: Allocator, P: GrowthPolicy> Ord for HeaderVec<H, T, A, P> {
     fn cmp(&self, rhs: &Self) -> Ordering {
         self.deref().cmp(rhs.deref())
     }
 }
 
-impl<H: Hash, T: Hash> Hash for HeaderVec<H, T> {
+impl<H: Hash, T: Hash, A: Allocator, P: GrowthPolicy> Hash for HeaderVec<H, T, A, P> {
     fn hash<S: hash::Hasher>(&self, state: &mut S) {
         self.deref().hash(state)
     }
 }
 
-impl<H: Debug, T: Debug> Debug for HeaderVec<H, T> {
+impl<H: Debug, T: Debug, A: Allocator, P: GrowthPolicy> Debug for HeaderVec<H, T, A, P> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let hslice: &HeaderSlice<H, T> = self.deref();
         hslice.fmt(f)
     }
 }
 
-impl<H: Default, T> iter::FromIterator<T> for HeaderVec<H, T> {
+impl<H: Default, T, A: Allocator + Default, P: GrowthPolicy> iter::FromIterator<T>
+    for HeaderVec<H, T, A, P>
+{
     fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
-        Self::from_iter(H::default(), iter)
+        HeaderVec::from_iter_in(H::default(), iter, A::default()).with_growth_policy()
     }
 }
 
@@ -576,38 +1296,183 @@ impl<H, T> From<HeaderVec<H, T>> for Box<HeaderSlice<H, T>> {
     }
 }
 
-impl<H: Default, T> Default for HeaderVec<H, T> {
+impl<T> From<Vec<T>> for HeaderVec<(), T> {
+    /// Converts a `Vec` into a `HeaderVec` with no header, reusing its allocation.
+    fn from(src: Vec<T>) -> Self {
+        // a `HeaderSlice<(), T>` has the same layout as `[T]`
+        let mut src = mem::ManuallyDrop::new(src);
+        let (len, cap) = (src.len(), src.capacity());
+        let ptr = NonNull::new(src.as_mut_ptr() as *mut Pair<(), MaybeUninit<T>>).unwrap();
+        unsafe { Self::from_raw_parts(ptr, len, cap) }
+    }
+}
+
+impl<T> From<HeaderVec<(), T>> for Vec<T> {
+    /// Converts a `HeaderVec` with no header into a `Vec`, reusing its allocation.
+    fn from(src: HeaderVec<(), T>) -> Self {
+        let (ptr, len, cap) = src.into_raw_parts();
+        unsafe { Vec::from_raw_parts(ptr.as_ptr() as *mut T, len, cap) }
+    }
+}
+
+impl<H, T> From<(H, Vec<T>)> for HeaderVec<H, T> {
+    /// Converts a header and a `Vec` into a `HeaderVec`, reusing the `Vec`'s allocation.
+    fn from((head, values): (H, Vec<T>)) -> Self {
+        Self::from_vec(head, values)
+    }
+}
+
+impl<H, T> From<HeaderVec<H, T>> for (H, Vec<T>) {
+    /// Splits a `HeaderVec` into its header and a `Vec`, reusing its allocation.
+    fn from(src: HeaderVec<H, T>) -> Self {
+        src.into_head_and_vec()
+    }
+}
+
+impl<H, T, const N: usize> From<(H, [T; N])> for HeaderVec<H, T> {
+    fn from((head, values): (H, [T; N])) -> Self {
+        Self::from_iter(head, values)
+    }
+}
+
+#[cfg(feature = "allocator-api2")]
+impl<H, T, A: Allocator> From<allocator_api2::boxed::Box<HeaderSlice<H, T>, A>>
+    for HeaderVec<H, T, A>
+{
+    fn from(src: allocator_api2::boxed::Box<HeaderSlice<H, T>, A>) -> Self {
+        Self::from_box_in(src)
+    }
+}
+
+#[cfg(feature = "allocator-api2")]
+impl<H, T, A: Allocator, P: GrowthPolicy> From<HeaderVec<H, T, A, P>>
+    for allocator_api2::boxed::Box<HeaderSlice<H, T>, A>
+{
+    fn from(src: HeaderVec<H, T, A, P>) -> Self {
+        src.into_box_in()
+    }
+}
+
+impl<H, T, A: Allocator, P: GrowthPolicy> IntoIterator for HeaderVec<H, T, A, P> {
+    type Item = T;
+    type IntoIter = IntoValuesIter<H, T, A, P>;
+    /// Consumes the vector and returns an iterator of its values.
+    /// The iterator keeps the header, which can be recovered with `IntoValuesIter::take_head`.
+    fn into_iter(self) -> Self::IntoIter {
+        let end = self.len;
+        IntoValuesIter {
+            inner: self.into_uninit(),
+            index: 0,
+            end,
+            has_head: true,
+        }
+    }
+}
+
+impl<'a, H, T, A: Allocator, P: GrowthPolicy> IntoIterator for &'a HeaderVec<H, T, A, P> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.body.iter()
+    }
+}
+
+impl<'a, H, T, A: Allocator, P: GrowthPolicy> IntoIterator for &'a mut HeaderVec<H, T, A, P> {
+    type Item = &'a mut T;
+    type IntoIter = slice::IterMut<'a, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.body.iter_mut()
+    }
+}
+
+impl<H: Default, T, A: Allocator + Default, P: GrowthPolicy> Default for HeaderVec<H, T, A, P> {
     fn default() -> Self {
-        Self::new(H::default())
+        HeaderVec::new_in(H::default(), A::default()).with_growth_policy()
     }
 }
 
-pub struct IntoValuesIter<H, T> {
-    inner: HeaderVec<MaybeUninit<H>, MaybeUninit<T>>,
+/// An iterator that moves the elements out of a `HeaderVec`.
+///
+/// If it was created with `into_iter`, the iterator also owns the vector's header, which can be
+/// recovered with `take_head`.
+pub struct IntoValuesIter<H, T, A: Allocator = Global, P: GrowthPolicy = DefaultGrowth> {
+    inner: HeaderVec<MaybeUninit<H>, MaybeUninit<T>, A, P>,
+    /// Index of the next element to yield from the front.
     index: usize,
+    /// Index after the next element to yield from the back.
+    end: usize,
+    /// Whether the header is still owned by the iterator.
+    has_head: bool,
 }
 
-impl<H, T> IntoValuesIter<H, T> {
-    fn valid_slice_ptr(this: *mut Self) -> *mut [T] {
-        let body = unsafe { &mut (*this).inner.body };
-        let index = unsafe { (*this).index };
-        &mut body[index..] as *mut [MaybeUninit<T>] as *mut [T]
+impl<H, T, A: Allocator, P: GrowthPolicy> IntoValuesIter<H, T, A, P> {
+    /// Returns a pointer to the elements that have not yet been yielded, derived from the
+    /// vector's own pointer so it can back both shared and mutable slices.
+    fn valid_slice_ptr(&self) -> *mut [T] {
+        let hslice = self.inner.as_ptr().as_ptr();
+        let body = unsafe { ptr::addr_of_mut!((*hslice).body) as *mut T };
+        ptr::slice_from_raw_parts_mut(unsafe { body.add(self.index) }, self.end - self.index)
     }
 
     /// Returns a slice of elements that have not yet been yielded by the iterator.
-    fn valid_slice(&self) -> &[T] {
-        unsafe { &*Self::valid_slice_ptr(self as *const Self as *mut Self) }
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { &*self.valid_slice_ptr() }
     }
+
     /// Returns a mutable slice of elements that have not yet been yielded by the iterator.
-    fn valid_slice_mut(&mut self) -> &mut [T] {
-        unsafe { &mut *Self::valid_slice_ptr(self as *mut Self) }
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe { &mut *self.valid_slice_ptr() }
+    }
+
+    /// Returns a reference to the header, if it is still owned by the iterator.
+    pub fn head(&self) -> Option<&H> {
+        if self.has_head {
+            Some(unsafe { &*self.inner.head.as_ptr() })
+        } else {
+            None
+        }
+    }
+
+    /// Moves the header out of the iterator, if it is still owned by the iterator.
+    pub fn take_head(&mut self) -> Option<H> {
+        if self.has_head {
+            self.has_head = false;
+            Some(unsafe { ptr::read(self.inner.head.as_ptr()) })
+        } else {
+            None
+        }
+    }
+
+    /// Converts the iterator back into a `HeaderVec` with the header `head`, holding the elements
+    /// that have not yet been yielded.
+    /// The allocation is reused. If the iterator still owns its old header, it is dropped.
+    pub fn into_header_vec(mut self, head: H) -> HeaderVec<H, T, A, P> {
+        drop(self.take_head());
+        let remaining = self.end - self.index;
+        let mut this = mem::ManuallyDrop::new(self);
+        let inner = unsafe { ptr::read(&this.inner) };
+        let (index, base) = (this.index, this.inner.body.as_mut_ptr());
+        let (ptr, _, cap, alloc) = inner.into_raw_parts_with_alloc();
+        unsafe {
+            if index > 0 {
+                ptr::copy(base.add(index), base, remaining);
+            }
+            let mut vec = HeaderVec::from_raw_parts_in(
+                ptr.cast::<Pair<H, MaybeUninit<T>>>(),
+                remaining,
+                cap,
+                alloc,
+            );
+            ptr::write(&mut vec.head, head);
+            vec.with_growth_policy()
+        }
     }
 }
 
-impl<H, T> Iterator for IntoValuesIter<H, T> {
+impl<H, T, A: Allocator, P: GrowthPolicy> Iterator for IntoValuesIter<H, T, A, P> {
     type Item = T;
     fn next(&mut self) -> Option<T> {
-        if self.index >= self.inner.len() {
+        if self.index >= self.end {
             return None;
         }
 
@@ -617,33 +1482,72 @@ impl<H, T> Iterator for IntoValuesIter<H, T> {
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        let len = self.inner.len() - self.index;
+        let len = self.end - self.index;
         (len, Some(len))
     }
 }
 
-impl<H, T> ExactSizeIterator for IntoValuesIter<H, T> {}
+impl<H, T, A: Allocator, P: GrowthPolicy> DoubleEndedIterator for IntoValuesIter<H, T, A, P> {
+    fn next_back(&mut self) -> Option<T> {
+        if self.index >= self.end {
+            return None;
+        }
+
+        self.end -= 1;
+        Some(unsafe { mem::transmute_copy(&self.inner.body[self.end]) })
+    }
+}
+
+impl<H, T, A: Allocator, P: GrowthPolicy> ExactSizeIterator for IntoValuesIter<H, T, A, P> {}
+
+impl<H, T, A: Allocator, P: GrowthPolicy> iter::FusedIterator for IntoValuesIter<H, T, A, P> {}
 
-impl<H, T> Drop for IntoValuesIter<H, T> {
+impl<H, T, A: Allocator, P: GrowthPolicy> Drop for IntoValuesIter<H, T, A, P> {
     fn drop(&mut self) {
+        // drop the header even if dropping an element panics
+        struct DropHead<'a, H, T, A: Allocator, P: GrowthPolicy>(
+            &'a mut IntoValuesIter<H, T, A, P>,
+        );
+
+        impl<H, T, A: Allocator, P: GrowthPolicy> Drop for DropHead<'_, H, T, A, P> {
+            fn drop(&mut self) {
+                drop(self.0.take_head());
+            }
+        }
+
+        let guard = DropHead(self);
         unsafe {
-            ptr::drop_in_place(self.valid_slice_mut());
+            ptr::drop_in_place(guard.0.as_mut_slice());
         }
     }
 }
 
-impl<H, T: Clone> Clone for IntoValuesIter<H, T> {
+impl<H, T: Clone, A: Allocator + Clone, P: GrowthPolicy> Clone for IntoValuesIter<H, T, A, P> {
+    /// Clones the remaining elements into a new allocation. The clone does not own a header.
     fn clone(&self) -> Self {
         // make an iterator that clones each element and converts them back to MaybeUninit
-        let iter = self.valid_slice().iter().cloned().map(MaybeUninit::new);
-        let new_vec = HeaderVec::from_iter(MaybeUninit::uninit(), iter);
+        let iter = self.as_slice().iter().cloned().map(MaybeUninit::new);
+        let alloc = self.inner.alloc.clone();
+        let new_vec =
+            HeaderVec::from_iter_in(MaybeUninit::uninit(), iter, alloc).with_growth_policy();
+        let end = new_vec.len();
         Self {
             inner: new_vec,
             index: 0,
+            end,
+            has_head: false,
         }
     }
 }
 
+impl<H, T: Debug, A: Allocator, P: GrowthPolicy> Debug for IntoValuesIter<H, T, A, P> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("IntoValuesIter")
+            .field(&self.as_slice())
+            .finish()
+    }
+}
+
 /// Creates a `HeaderVec` with the given header and elements;
 ///
 /// ## Examples:
@@ -653,13 +1557,13 @@ impl<H, T: Clone> Clone for IntoValuesIter<H, T> {
 macro_rules! header_vec {
     // Take a list of elements:
     ($h:expr; $($v:expr),* $(,)?) => {{
-        let mut src = [$($v),*];
+        let head = $h;
+        let mut src = core::mem::ManuallyDrop::new([$($v),*]);
+        let (src_ptr, src_len) = (src.as_mut_ptr(), src.len());
         #[allow(unused_unsafe)]
-        let v = unsafe {
-            $crate::vec::HeaderVec::copy_from_ptr_unsafe($h, src.as_mut_ptr(), src.len())
-        };
-        core::mem::forget(src);
-        v
+        unsafe {
+            $crate::vec::HeaderVec::copy_from_ptr_unsafe(head, src_ptr, src_len)
+        }
     }};
     // Take a cloneable element and desired length:
     ($h:expr; $v:expr; $len:expr) => {{
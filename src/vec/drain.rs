@@ -0,0 +1,198 @@
+use super::HeaderVec;
+use crate::allocator::{Allocator, Global};
+use crate::growth::{DefaultGrowth, GrowthPolicy};
+use core::fmt::{self, Debug};
+use core::iter::FusedIterator;
+use core::marker::PhantomData;
+use core::mem::{self, ManuallyDrop};
+use core::ptr::{self, NonNull};
+use core::slice;
+
+/// An iterator that removes a range of elements from a `HeaderVec` and yields them by value.
+///
+/// Elements after the range are moved back into place once the iterator is dropped. If the
+/// iterator is leaked, the vector is left truncated to the start of the range.
+pub struct Drain<'a, H, T, A: Allocator = Global, P: GrowthPolicy = DefaultGrowth> {
+    vec: NonNull<HeaderVec<H, T, A, P>>,
+    /// Elements in the range that haven't been yielded yet.
+    iter: slice::Iter<'a, T>,
+    /// Index of the first element after the range.
+    tail_start: usize,
+    /// Number of elements after the range.
+    tail_len: usize,
+    _marker: PhantomData<&'a mut HeaderVec<H, T, A, P>>,
+}
+
+/// Returns a pointer to the same `len` elements as `ptr`, derived from `base` so it can be written
+/// through. `ptr` must point into the allocation that starts at `base` unless `len` is zero.
+unsafe fn rebase<T>(ptr: *const T, base: *mut T, len: usize) -> *mut T {
+    if len == 0 || mem::size_of::<T>() == 0 {
+        return base;
+    }
+    base.add(ptr.offset_from(base) as usize)
+}
+
+impl<'a, H, T, A: Allocator, P: GrowthPolicy> Drain<'a, H, T, A, P> {
+    /// Removes the elements in `start..end` from `vec`, to be yielded by the new iterator.
+    /// The caller must make sure `start <= end <= vec.len()`.
+    pub(super) fn new(vec: &'a mut HeaderVec<H, T, A, P>, start: usize, end: usize) -> Self {
+        let len = vec.len;
+        // until the iterator is dropped, the vector only owns elements before the range
+        vec.len = start;
+        let base = vec.inner_mut().body.as_ptr() as *const T;
+        let range = unsafe { slice::from_raw_parts(base.add(start), end - start) };
+        Self {
+            vec: NonNull::from(vec),
+            iter: range.iter(),
+            tail_start: end,
+            tail_len: len - end,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the elements that have not yet been yielded by the iterator.
+    pub fn as_slice(&self) -> &[T] {
+        self.iter.as_slice()
+    }
+
+    /// Keeps the elements that have not yet been yielded in the vector instead of dropping them.
+    pub fn keep_rest(self) {
+        let mut this = ManuallyDrop::new(self);
+        let unyielded_len = this.iter.len();
+        let unyielded_ptr = this.iter.as_slice().as_ptr();
+        let vec = unsafe { this.vec.as_mut() };
+        let start = vec.len;
+        let base = vec.inner_mut().body.as_mut_ptr() as *mut T;
+        unsafe {
+            // derive the pointer from the vector, since the iterator only grants shared access
+            let unyielded_ptr = rebase(unyielded_ptr, base, unyielded_len);
+            let start_ptr = base.add(start);
+            if !ptr::eq(unyielded_ptr, start_ptr) {
+                ptr::copy(unyielded_ptr, start_ptr, unyielded_len);
+            }
+            let new_tail_start = start + unyielded_len;
+            if this.tail_start != new_tail_start {
+                ptr::copy(
+                    base.add(this.tail_start),
+                    base.add(new_tail_start),
+                    this.tail_len,
+                );
+            }
+            vec.len = new_tail_start + this.tail_len;
+        }
+    }
+}
+
+impl<H, T, A: Allocator, P: GrowthPolicy> Drain<'_, H, T, A, P> {
+    /// Returns the vector being drained.
+    pub(super) fn vec_mut(&mut self) -> &mut HeaderVec<H, T, A, P> {
+        unsafe { self.vec.as_mut() }
+    }
+
+    /// Returns the number of elements after the drained range.
+    pub(super) fn tail_len(&self) -> usize {
+        self.tail_len
+    }
+
+    /// Fills the gap between the vector's length and the tail with values from `replace_with`.
+    /// Returns `true` if the gap was filled, or `false` if `replace_with` ran out first.
+    pub(super) fn fill<I: Iterator<Item = T>>(&mut self, replace_with: &mut I) -> bool {
+        let vec = unsafe { self.vec.as_mut() };
+        let base = vec.inner_mut().body.as_mut_ptr() as *mut T;
+        while vec.len < self.tail_start {
+            match replace_with.next() {
+                Some(val) => unsafe {
+                    ptr::write(base.add(vec.len), val);
+                    vec.len += 1;
+                },
+                None => return false,
+            }
+        }
+        true
+    }
+
+    /// Moves the tail right by `additional` elements, growing the vector if necessary.
+    pub(super) fn move_tail(&mut self, additional: usize) {
+        let vec = unsafe { self.vec.as_mut() };
+        let used = self.tail_start + self.tail_len;
+        let target_len = used.checked_add(additional).expect("capacity overflow");
+        if target_len > vec.capacity() {
+            vec.grow(target_len);
+        }
+        let base = vec.inner_mut().body.as_mut_ptr() as *mut T;
+        let new_tail_start = self.tail_start + additional;
+        unsafe {
+            ptr::copy(
+                base.add(self.tail_start),
+                base.add(new_tail_start),
+                self.tail_len,
+            );
+        }
+        self.tail_start = new_tail_start;
+    }
+}
+
+impl<H, T, A: Allocator, P: GrowthPolicy> Iterator for Drain<'_, H, T, A, P> {
+    type Item = T;
+    fn next(&mut self) -> Option<T> {
+        self.iter.next().map(|val| unsafe { ptr::read(val) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<H, T, A: Allocator, P: GrowthPolicy> DoubleEndedIterator for Drain<'_, H, T, A, P> {
+    fn next_back(&mut self) -> Option<T> {
+        self.iter.next_back().map(|val| unsafe { ptr::read(val) })
+    }
+}
+
+impl<H, T, A: Allocator, P: GrowthPolicy> ExactSizeIterator for Drain<'_, H, T, A, P> {}
+
+impl<H, T, A: Allocator, P: GrowthPolicy> FusedIterator for Drain<'_, H, T, A, P> {}
+
+impl<H, T, A: Allocator, P: GrowthPolicy> Drop for Drain<'_, H, T, A, P> {
+    fn drop(&mut self) {
+        /// Moves the tail back into place, even if dropping an element panics.
+        struct DropGuard<'r, 'a, H, T, A: Allocator, P: GrowthPolicy>(
+            &'r mut Drain<'a, H, T, A, P>,
+        );
+
+        impl<H, T, A: Allocator, P: GrowthPolicy> Drop for DropGuard<'_, '_, H, T, A, P> {
+            fn drop(&mut self) {
+                let drain = &mut *self.0;
+                if drain.tail_len == 0 {
+                    return;
+                }
+                let vec = unsafe { drain.vec.as_mut() };
+                let start = vec.len;
+                if drain.tail_start != start {
+                    let base = vec.inner_mut().body.as_mut_ptr() as *mut T;
+                    unsafe {
+                        ptr::copy(base.add(drain.tail_start), base.add(start), drain.tail_len)
+                    };
+                }
+                vec.len = start + drain.tail_len;
+            }
+        }
+
+        let iter = mem::take(&mut self.iter);
+        let (remaining_ptr, remaining_len) = (iter.as_slice().as_ptr(), iter.len());
+        // derive the pointer from the vector, since the iterator only grants shared access
+        let vec = unsafe { self.vec.as_mut() };
+        let base = vec.inner_mut().body.as_mut_ptr() as *mut T;
+        let to_drop = unsafe {
+            ptr::slice_from_raw_parts_mut(rebase(remaining_ptr, base, remaining_len), remaining_len)
+        };
+        let _guard = DropGuard(self);
+        unsafe { ptr::drop_in_place(to_drop) };
+    }
+}
+
+impl<H, T: Debug, A: Allocator, P: GrowthPolicy> Debug for Drain<'_, H, T, A, P> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Drain").field(&self.as_slice()).finish()
+    }
+}
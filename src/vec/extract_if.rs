@@ -0,0 +1,121 @@
+use super::HeaderVec;
+use crate::allocator::{Allocator, Global};
+use crate::growth::{DefaultGrowth, GrowthPolicy};
+use crate::pair::{pair_as_slice_ptr, Pair};
+use core::fmt::{self, Debug};
+use core::iter::FusedIterator;
+use core::ptr;
+
+/// An iterator that lazily removes the elements of a `HeaderVec` that match a predicate and
+/// yields them by value.
+///
+/// When the iterator is dropped, the remaining elements are shifted back to close any gaps. If
+/// it is leaked, the vector is left empty.
+pub struct ExtractIf<
+    'a,
+    H,
+    T,
+    F: FnMut(&mut T) -> bool,
+    A: Allocator = Global,
+    P: GrowthPolicy = DefaultGrowth,
+> {
+    vec: &'a mut HeaderVec<H, T, A, P>,
+    /// Index of the next element to visit.
+    idx: usize,
+    /// Index after the last element to visit.
+    end: usize,
+    /// Number of elements removed so far.
+    del: usize,
+    /// Length of the vector before the iterator was created.
+    old_len: usize,
+    pred: F,
+}
+
+impl<'a, H, T, F: FnMut(&mut T) -> bool, A: Allocator, P: GrowthPolicy>
+    ExtractIf<'a, H, T, F, A, P>
+{
+    /// The caller must make sure `start <= end <= vec.len()`.
+    pub(super) fn new(
+        vec: &'a mut HeaderVec<H, T, A, P>,
+        start: usize,
+        end: usize,
+        pred: F,
+    ) -> Self {
+        let old_len = vec.len;
+        // until the iterator is dropped, the vector doesn't own any elements
+        vec.len = 0;
+        Self {
+            vec,
+            idx: start,
+            end,
+            del: 0,
+            old_len,
+            pred,
+        }
+    }
+
+    fn base(&mut self) -> *mut T {
+        self.vec.inner_mut().body.as_mut_ptr() as *mut T
+    }
+}
+
+impl<H, T, F: FnMut(&mut T) -> bool, A: Allocator, P: GrowthPolicy> Iterator
+    for ExtractIf<'_, H, T, F, A, P>
+{
+    type Item = T;
+    fn next(&mut self) -> Option<T> {
+        let base = self.base();
+        while self.idx < self.end {
+            let cur = unsafe { base.add(self.idx) };
+            let extract = (self.pred)(unsafe { &mut *cur });
+            // only count the element as visited once the predicate has returned, so a panic
+            // leaves it in place
+            self.idx += 1;
+            if extract {
+                self.del += 1;
+                return Some(unsafe { ptr::read(cur) });
+            } else if self.del > 0 {
+                unsafe { ptr::copy_nonoverlapping(cur, cur.sub(self.del), 1) };
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.end - self.idx))
+    }
+}
+
+impl<H, T, F: FnMut(&mut T) -> bool, A: Allocator, P: GrowthPolicy> FusedIterator
+    for ExtractIf<'_, H, T, F, A, P>
+{
+}
+
+impl<H, T, F: FnMut(&mut T) -> bool, A: Allocator, P: GrowthPolicy> Drop
+    for ExtractIf<'_, H, T, F, A, P>
+{
+    fn drop(&mut self) {
+        if self.del > 0 && self.idx < self.old_len {
+            let base = self.base();
+            unsafe {
+                ptr::copy(
+                    base.add(self.idx),
+                    base.add(self.idx - self.del),
+                    self.old_len - self.idx,
+                );
+            }
+        }
+        self.vec.len = self.old_len - self.del;
+    }
+}
+
+impl<H, T: Debug, F: FnMut(&mut T) -> bool, A: Allocator, P: GrowthPolicy> Debug
+    for ExtractIf<'_, H, T, F, A, P>
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let ptr = pair_as_slice_ptr(self.vec.ptr.cast::<Pair<H, T>>(), self.old_len);
+        let slice = unsafe { ptr.as_ref() };
+        let unvisited = &slice.body[self.idx..self.end];
+        f.debug_tuple("ExtractIf").field(&unvisited).finish()
+    }
+}
@@ -0,0 +1,108 @@
+use super::Drain;
+use crate::allocator::{Allocator, Global};
+use crate::growth::{DefaultGrowth, GrowthPolicy};
+use alloc::vec::Vec;
+use core::fmt::{self, Debug};
+use core::iter::FusedIterator;
+
+/// An iterator that replaces a range of elements in a `HeaderVec` with the contents of another
+/// iterator, yielding the removed elements by value.
+///
+/// The replacement happens when the iterator is dropped, whether or not the removed elements
+/// were consumed.
+pub struct Splice<
+    'a,
+    H,
+    T,
+    I: Iterator<Item = T>,
+    A: Allocator = Global,
+    P: GrowthPolicy = DefaultGrowth,
+> {
+    drain: Drain<'a, H, T, A, P>,
+    replace_with: I,
+}
+
+impl<'a, H, T, I: Iterator<Item = T>, A: Allocator, P: GrowthPolicy> Splice<'a, H, T, I, A, P> {
+    pub(super) fn new(drain: Drain<'a, H, T, A, P>, replace_with: I) -> Self {
+        Self {
+            drain,
+            replace_with,
+        }
+    }
+}
+
+impl<H, T, I: Iterator<Item = T>, A: Allocator, P: GrowthPolicy> Iterator
+    for Splice<'_, H, T, I, A, P>
+{
+    type Item = T;
+    fn next(&mut self) -> Option<T> {
+        self.drain.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.drain.size_hint()
+    }
+}
+
+impl<H, T, I: Iterator<Item = T>, A: Allocator, P: GrowthPolicy> DoubleEndedIterator
+    for Splice<'_, H, T, I, A, P>
+{
+    fn next_back(&mut self) -> Option<T> {
+        self.drain.next_back()
+    }
+}
+
+impl<H, T, I: Iterator<Item = T>, A: Allocator, P: GrowthPolicy> ExactSizeIterator
+    for Splice<'_, H, T, I, A, P>
+{
+}
+
+impl<H, T, I: Iterator<Item = T>, A: Allocator, P: GrowthPolicy> FusedIterator
+    for Splice<'_, H, T, I, A, P>
+{
+}
+
+impl<H, T, I: Iterator<Item = T>, A: Allocator, P: GrowthPolicy> Drop
+    for Splice<'_, H, T, I, A, P>
+{
+    fn drop(&mut self) {
+        self.drain.by_ref().for_each(drop);
+
+        if self.drain.tail_len() == 0 {
+            self.drain.vec_mut().extend(self.replace_with.by_ref());
+            return;
+        }
+
+        // fill the drained range, then make room for as many more values as the iterator
+        // promises so the tail is usually only moved once
+        if !self.drain.fill(&mut self.replace_with) {
+            return;
+        }
+        let (lower, _) = self.replace_with.size_hint();
+        if lower > 0 {
+            self.drain.move_tail(lower);
+            if !self.drain.fill(&mut self.replace_with) {
+                return;
+            }
+        }
+
+        // the size hint was too low, so collect the rest to find out how many are left
+        let mut collected = self.replace_with.by_ref().collect::<Vec<T>>().into_iter();
+        if collected.len() > 0 {
+            self.drain.move_tail(collected.len());
+            self.drain.fill(&mut collected);
+        }
+        // dropping the drain moves the tail back to the end of the filled range
+    }
+}
+
+impl<H, T: Debug, I: Iterator<Item = T> + Debug, A: Allocator, P: GrowthPolicy> Debug
+    for Splice<'_, H, T, I, A, P>
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Splice")
+            .field("drain", &self.drain)
+            .field("replace_with", &self.replace_with)
+            .finish()
+    }
+}
@@ -0,0 +1,370 @@
+//! Borrowed views of a header together with some or all of a body.
+//!
+//! A `&HeaderSlice` always covers the whole body, so it can't describe a sub-range of elements.
+//! `HeaderSliceRef` and `HeaderSliceMut` hold the header and the elements as separate references,
+//! so they can be split and narrowed while keeping the header attached. Converting a
+//! `&HeaderSlice` or `&HeaderVec` into a view is free, so a function taking
+//! `impl Into<HeaderSliceRef<H, T>>` accepts all of them.
+
+#[cfg(feature = "alloc")]
+use crate::allocator::Allocator;
+#[cfg(feature = "alloc")]
+use crate::growth::GrowthPolicy;
+use crate::slice::{self as header_slice, HeaderSlice};
+#[cfg(feature = "alloc")]
+use crate::vec::HeaderVec;
+use core::cmp::Ordering;
+use core::fmt::{self, Debug};
+use core::hash::{self, Hash};
+use core::iter::FusedIterator;
+use core::ops::{Bound, RangeBounds};
+use core::slice;
+
+/// A shared reference to a header and a run of elements.
+pub struct HeaderSliceRef<'a, H, T> {
+    pub head: &'a H,
+    pub body: &'a [T],
+}
+
+/// A mutable reference to a header and a run of elements.
+pub struct HeaderSliceMut<'a, H, T> {
+    pub head: &'a mut H,
+    pub body: &'a mut [T],
+}
+
+/// Converts `range` to bounds that can index a slice.
+fn to_bounds<R: RangeBounds<usize>>(range: R) -> (Bound<usize>, Bound<usize>) {
+    (range.start_bound().cloned(), range.end_bound().cloned())
+}
+
+impl<'a, H, T> HeaderSliceRef<'a, H, T> {
+    /// Creates a view of `head` and `body`.
+    pub fn new(head: &'a H, body: &'a [T]) -> Self {
+        Self { head, body }
+    }
+
+    /// The number of elements in the view.
+    pub fn len(&self) -> usize {
+        self.body.len()
+    }
+
+    /// Returns `true` if the view has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.body.is_empty()
+    }
+
+    /// Returns the view of the elements in `range`, or `None` if it is out of bounds.
+    pub fn get<R: RangeBounds<usize>>(self, range: R) -> Option<Self> {
+        let body = self.body.get(to_bounds(range))?;
+        Some(Self::new(self.head, body))
+    }
+
+    /// Splits the view into the elements before `mid` and the elements from `mid` on, both with
+    /// the same header.
+    /// Panics if `mid > len`.
+    pub fn split_at(self, mid: usize) -> (Self, Self) {
+        let (front, back) = self.body.split_at(mid);
+        (Self::new(self.head, front), Self::new(self.head, back))
+    }
+
+    /// Returns an iterator over the elements.
+    pub fn iter(self) -> slice::Iter<'a, T> {
+        self.body.iter()
+    }
+
+    /// Returns an iterator over views of `size` elements at a time, each with the same header.
+    /// The last view has fewer elements if `size` doesn't divide the length.
+    /// Panics if `size` is 0.
+    pub fn chunks(self, size: usize) -> Chunks<'a, H, T> {
+        Chunks {
+            head: self.head,
+            inner: self.body.chunks(size),
+        }
+    }
+
+    /// Returns an iterator over every overlapping view of `size` consecutive elements, each with
+    /// the same header.
+    /// Panics if `size` is 0.
+    pub fn windows(self, size: usize) -> Windows<'a, H, T> {
+        Windows {
+            head: self.head,
+            inner: self.body.windows(size),
+        }
+    }
+
+    /// Clones the header and elements into a new `HeaderVec`.
+    #[cfg(feature = "alloc")]
+    pub fn to_header_vec(&self) -> HeaderVec<H, T>
+    where
+        H: Clone,
+        T: Clone,
+    {
+        let mut out = HeaderVec::with_capacity(self.head.clone(), self.body.len());
+        out.extend_from_slice(self.body);
+        out
+    }
+}
+
+impl<'a, H, T> HeaderSliceMut<'a, H, T> {
+    /// Creates a view of `head` and `body`.
+    pub fn new(head: &'a mut H, body: &'a mut [T]) -> Self {
+        Self { head, body }
+    }
+
+    /// The number of elements in the view.
+    pub fn len(&self) -> usize {
+        self.body.len()
+    }
+
+    /// Returns `true` if the view has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.body.is_empty()
+    }
+
+    /// Borrows this view as a shared view.
+    pub fn as_view(&self) -> HeaderSliceRef<'_, H, T> {
+        HeaderSliceRef::new(self.head, self.body)
+    }
+
+    /// Borrows this view for a shorter lifetime, leaving it usable afterwards.
+    pub fn reborrow(&mut self) -> HeaderSliceMut<'_, H, T> {
+        HeaderSliceMut::new(self.head, self.body)
+    }
+
+    /// Converts this view into a shared view with the same lifetime.
+    pub fn into_ref(self) -> HeaderSliceRef<'a, H, T> {
+        HeaderSliceRef::new(self.head, self.body)
+    }
+
+    /// Returns the shared view of the elements in `range`, or `None` if it is out of bounds.
+    pub fn get<R: RangeBounds<usize>>(&self, range: R) -> Option<HeaderSliceRef<'_, H, T>> {
+        self.as_view().get(range)
+    }
+
+    /// Returns the mutable view of the elements in `range`, or `None` if it is out of bounds.
+    pub fn get_mut<R: RangeBounds<usize>>(self, range: R) -> Option<Self> {
+        let body = self.body.get_mut(to_bounds(range))?;
+        Some(Self::new(self.head, body))
+    }
+
+    /// Splits the view into shared views of the elements before `mid` and the elements from
+    /// `mid` on, both with the same header.
+    /// Panics if `mid > len`.
+    pub fn split_at(&self, mid: usize) -> (HeaderSliceRef<'_, H, T>, HeaderSliceRef<'_, H, T>) {
+        self.as_view().split_at(mid)
+    }
+
+    /// Splits the view at `mid`. The header stays with the elements before `mid`, and the
+    /// elements from `mid` on are returned as a plain slice, since the header can only be
+    /// borrowed mutably once.
+    /// Panics if `mid > len`.
+    pub fn split_at_mut(self, mid: usize) -> (Self, &'a mut [T]) {
+        let (front, back) = self.body.split_at_mut(mid);
+        (Self::new(self.head, front), back)
+    }
+
+    /// Returns an iterator over the elements.
+    pub fn iter(&self) -> slice::Iter<'_, T> {
+        self.body.iter()
+    }
+
+    /// Returns an iterator over mutable references to the elements.
+    pub fn iter_mut(&mut self) -> slice::IterMut<'_, T> {
+        self.body.iter_mut()
+    }
+
+    /// Returns an iterator over shared views of `size` elements at a time, each with the same
+    /// header.
+    /// Panics if `size` is 0.
+    pub fn chunks(&self, size: usize) -> Chunks<'_, H, T> {
+        self.as_view().chunks(size)
+    }
+
+    /// Returns an iterator over every overlapping shared view of `size` consecutive elements,
+    /// each with the same header.
+    /// Panics if `size` is 0.
+    pub fn windows(&self, size: usize) -> Windows<'_, H, T> {
+        self.as_view().windows(size)
+    }
+
+    /// Clones the header and elements into a new `HeaderVec`.
+    #[cfg(feature = "alloc")]
+    pub fn to_header_vec(&self) -> HeaderVec<H, T>
+    where
+        H: Clone,
+        T: Clone,
+    {
+        self.as_view().to_header_vec()
+    }
+}
+
+impl<H, T> Clone for HeaderSliceRef<'_, H, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<H, T> Copy for HeaderSliceRef<'_, H, T> {}
+
+impl<'a, H, T> From<&'a HeaderSlice<H, T>> for HeaderSliceRef<'a, H, T> {
+    fn from(src: &'a HeaderSlice<H, T>) -> Self {
+        src.as_view()
+    }
+}
+
+impl<'a, H, T> From<&'a mut HeaderSlice<H, T>> for HeaderSliceMut<'a, H, T> {
+    fn from(src: &'a mut HeaderSlice<H, T>) -> Self {
+        src.as_view_mut()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, H, T, A: Allocator, P: GrowthPolicy> From<&'a HeaderVec<H, T, A, P>>
+    for HeaderSliceRef<'a, H, T>
+{
+    fn from(src: &'a HeaderVec<H, T, A, P>) -> Self {
+        src.as_view()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, H, T, A: Allocator, P: GrowthPolicy> From<&'a mut HeaderVec<H, T, A, P>>
+    for HeaderSliceMut<'a, H, T>
+{
+    fn from(src: &'a mut HeaderVec<H, T, A, P>) -> Self {
+        src.as_view_mut()
+    }
+}
+
+impl<'a, H, T> From<HeaderSliceMut<'a, H, T>> for HeaderSliceRef<'a, H, T> {
+    fn from(src: HeaderSliceMut<'a, H, T>) -> Self {
+        src.into_ref()
+    }
+}
+
+impl<'a, H, T> IntoIterator for HeaderSliceRef<'a, H, T> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.body.iter()
+    }
+}
+
+impl<'a, H, T> IntoIterator for HeaderSliceMut<'a, H, T> {
+    type Item = &'a mut T;
+    type IntoIter = slice::IterMut<'a, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.body.iter_mut()
+    }
+}
+
+impl<H: PartialEq, T: PartialEq> PartialEq for HeaderSliceRef<'_, H, T> {
+    fn eq(&self, rhs: &Self) -> bool {
+        self.head == rhs.head && self.body == rhs.body
+    }
+}
+
+impl<H: Eq, T: Eq> Eq for HeaderSliceRef<'_, H, T> {}
+
+impl<H: PartialOrd, T: PartialOrd> PartialOrd for HeaderSliceRef<'_, H, T> {
+    fn partial_cmp(&self, rhs: &Self) -> Option<Ordering> {
+        partial_ord_chain! {
+            self.head => rhs.head,
+            self.body => rhs.body,
+        }
+    }
+}
+
+impl<H: Ord, T: Ord> Ord for HeaderSliceRef<'_, H, T> {
+    fn cmp(&self, rhs: &Self) -> Ordering {
+        ord_chain! {
+            self.head => rhs.head,
+            self.body => rhs.body,
+        }
+    }
+}
+
+/// Hashes the same way as a `HeaderSlice` with the same header and elements.
+impl<H: Hash, T: Hash> Hash for HeaderSliceRef<'_, H, T> {
+    fn hash<S: hash::Hasher>(&self, state: &mut S) {
+        self.head.hash(state);
+        self.body.hash(state);
+    }
+}
+
+impl<H: Debug, T: Debug> Debug for HeaderSliceRef<'_, H, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        header_slice::fmt_parts(self.head, self.body, f)
+    }
+}
+
+impl<H: Debug, T: Debug> Debug for HeaderSliceMut<'_, H, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        header_slice::fmt_parts(self.head, self.body, f)
+    }
+}
+
+/// An iterator over views of non-overlapping chunks of elements, created by
+/// `HeaderSliceRef::chunks`.
+pub struct Chunks<'a, H, T> {
+    head: &'a H,
+    inner: slice::Chunks<'a, T>,
+}
+
+/// An iterator over views of overlapping windows of elements, created by
+/// `HeaderSliceRef::windows`.
+pub struct Windows<'a, H, T> {
+    head: &'a H,
+    inner: slice::Windows<'a, T>,
+}
+
+macro_rules! view_iter_impls {
+    ($name:ident) => {
+        impl<'a, H, T> Iterator for $name<'a, H, T> {
+            type Item = HeaderSliceRef<'a, H, T>;
+            fn next(&mut self) -> Option<Self::Item> {
+                let body = self.inner.next()?;
+                Some(HeaderSliceRef::new(self.head, body))
+            }
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                self.inner.size_hint()
+            }
+            fn nth(&mut self, n: usize) -> Option<Self::Item> {
+                let body = self.inner.nth(n)?;
+                Some(HeaderSliceRef::new(self.head, body))
+            }
+        }
+
+        impl<H, T> DoubleEndedIterator for $name<'_, H, T> {
+            fn next_back(&mut self) -> Option<Self::Item> {
+                let body = self.inner.next_back()?;
+                Some(HeaderSliceRef::new(self.head, body))
+            }
+        }
+
+        impl<H, T> ExactSizeIterator for $name<'_, H, T> {}
+
+        impl<H, T> FusedIterator for $name<'_, H, T> {}
+
+        impl<H, T> Clone for $name<'_, H, T> {
+            fn clone(&self) -> Self {
+                Self {
+                    head: self.head,
+                    inner: self.inner.clone(),
+                }
+            }
+        }
+
+        impl<H: Debug, T: Debug> Debug for $name<'_, H, T> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.debug_struct(stringify!($name))
+                    .field("head", self.head)
+                    .field("inner", &self.inner)
+                    .finish()
+            }
+        }
+    };
+}
+
+view_iter_impls!(Chunks);
+view_iter_impls!(Windows);